@@ -0,0 +1,28 @@
+use crate::api::error::AppResult;
+use crate::models::alias::GameAlias;
+use crate::services::alias;
+use std::path::PathBuf;
+
+/// 获取别名列表，可按目录 ID 过滤
+#[tauri::command]
+pub async fn list_aliases(trainer_id: Option<String>) -> AppResult<Vec<GameAlias>> {
+    alias::list(trainer_id).await
+}
+
+/// 添加或更新别名
+#[tauri::command]
+pub async fn save_alias(alias: GameAlias) -> AppResult<()> {
+    alias::save(alias).await
+}
+
+/// 删除别名
+#[tauri::command]
+pub async fn delete_alias(alias: GameAlias) -> AppResult<()> {
+    alias::remove(alias).await
+}
+
+/// 从 CSV/JSON 文件导入别名，返回导入条数
+#[tauri::command]
+pub async fn import_aliases(path: String) -> AppResult<u32> {
+    alias::import_file(&PathBuf::from(path)).await
+}
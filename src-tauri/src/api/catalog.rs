@@ -0,0 +1,39 @@
+use crate::api::error::AppResult;
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::Trainer;
+use crate::services::catalog::{self, CatalogStatus};
+use tauri::Runtime;
+
+/// 在后台同步修改器目录，full 为 true 时重新抓取全部页面
+#[tauri::command]
+pub fn start_catalog_sync<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    full: Option<bool>,
+) -> AppResult<()> {
+    catalog::start_sync(app_handle, full.unwrap_or(false))
+}
+
+/// 在本地目录中全文搜索，目录为空时回退到远程搜索
+#[tauri::command]
+pub async fn search_local(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    catalog::search_local(query, page, source).await
+}
+
+/// 获取目录同步状态
+#[tauri::command]
+pub async fn get_catalog_status() -> AppResult<CatalogStatus> {
+    catalog::get_status().await
+}
+
+/// 从本地目录分页浏览修改器
+#[tauri::command]
+pub async fn get_catalog_trainers(
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    catalog::get_catalog_trainers(page, source).await
+}
@@ -18,6 +18,7 @@ pub enum ErrorCode {
     NotFound = 9000,
     Permission = 10000,
     Execution = 11000,
+    Cancelled = 12000,
     Unknown = 99999,
 }
 
@@ -58,6 +59,12 @@ pub enum AppError {
     #[error("执行错误: {0}")]
     ExecutionError(String),
 
+    #[error("离线模式: {0}")]
+    OfflineError(String),
+
+    #[error("已取消: {0}")]
+    CancelledError(String),
+
     #[error("未知错误: {0}")]
     UnknownError(String),
 }
@@ -96,6 +103,8 @@ impl AppError {
             AppError::NotFoundError(_) => ErrorCode::NotFound,
             AppError::PermissionError(_) => ErrorCode::Permission,
             AppError::ExecutionError(_) => ErrorCode::Execution,
+            AppError::OfflineError(_) => ErrorCode::Network,
+            AppError::CancelledError(_) => ErrorCode::Cancelled,
             AppError::UnknownError(_) => ErrorCode::Unknown,
         }
     }
@@ -129,6 +138,8 @@ impl AppError {
             AppError::NotFoundError(msg) => format!("未找到资源: {}", msg),
             AppError::PermissionError(_) => "权限不足，请以管理员身份运行应用程序".to_string(),
             AppError::ExecutionError(_) => "执行操作失败，请确保系统满足运行要求".to_string(),
+            AppError::OfflineError(_) => "当前处于离线模式，且没有可用的缓存数据".to_string(),
+            AppError::CancelledError(_) => "下载已取消".to_string(),
             AppError::UnknownError(_) => "发生未知错误，请尝试重启应用程序".to_string(),
         }
     }
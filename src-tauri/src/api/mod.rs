@@ -1,4 +1,6 @@
 pub mod admin;
+pub mod alias;
+pub mod catalog;
 pub mod error;
 pub mod settings;
 pub mod storage;
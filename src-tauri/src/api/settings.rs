@@ -1,5 +1,10 @@
 use crate::api::error::AppResult;
-use crate::services::settings::{self, AppSettings};
+use crate::services::bandwidth;
+use crate::services::download_manager;
+use crate::services::http::{self, ProxyTestResult};
+use crate::services::http_cache;
+use crate::services::offline::{self, NetworkStatus};
+use crate::services::settings::{self, AppSettings, ProxySettings};
 use tauri_plugin_dialog::DialogExt;
 
 /// 获取应用设置
@@ -11,7 +16,30 @@ pub fn get_settings() -> AppResult<AppSettings> {
 /// 保存应用设置
 #[tauri::command]
 pub fn save_settings(settings: AppSettings) -> AppResult<()> {
-    settings::save_settings(&settings)
+    settings::save_settings(&settings)?;
+    download_manager::refresh_queue(&settings);
+    bandwidth::apply(&settings.bandwidth);
+    offline::apply(settings.offline_mode);
+    http_cache::apply(settings.stale_while_revalidate);
+    http::apply_proxy(&settings.proxy)
+}
+
+/// 获取网络状态（是否处于离线模式）
+#[tauri::command]
+pub fn get_network_status() -> NetworkStatus {
+    offline::status()
+}
+
+/// 测试代理连通性，未指定时使用已保存的代理设置和 FLiNG 站点地址
+#[tauri::command]
+pub async fn test_proxy(
+    proxy: Option<ProxySettings>,
+    url: Option<String>,
+) -> AppResult<ProxyTestResult> {
+    let current = settings::load_settings()?;
+    let proxy = proxy.unwrap_or(current.proxy);
+    let url = url.unwrap_or(current.fling_base_url);
+    http::test_proxy(&proxy, &url).await
 }
 
 /// 获取下载路径
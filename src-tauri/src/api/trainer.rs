@@ -1,5 +1,10 @@
 use crate::api::error::AppResult;
+use crate::models::revision::{TrainerChangelog, TrainerRevision};
 use crate::models::trainer::Trainer;
+use crate::services::scraper_health::{self, HealthReport};
+use crate::services::revision;
+use crate::services::scraper_rules;
+use crate::services::sources::{self, SourceInfo};
 use crate::services::trainer as trainer_service;
 use serde::Serialize;
 use std::path::PathBuf;
@@ -10,6 +15,18 @@ use tauri::Runtime;
 pub struct PaginatedResponse<T> {
     pub trainers: Vec<T>,
     pub total: u32,
+    pub total_pages: u32,
+    pub current_page: u32,
+    pub has_next: bool,
+    pub stale: bool, // 离线时返回的缓存或同步数据
+}
+
+// 修改器详情响应，字段与 Trainer 相同并附加离线标记
+#[derive(Serialize)]
+pub struct TrainerDetail {
+    #[serde(flatten)]
+    pub trainer: Trainer,
+    pub stale: bool,
 }
 
 // 下载进度响应
@@ -19,39 +36,90 @@ pub struct DownloadProgress {
     pub progress: f64, // 0-100
     pub downloaded_bytes: u64,
     pub total_bytes: Option<u64>,
-    pub status: String,     // "downloading", "completed", "error"
+    pub status: String,     // "queued", "downloading", "paused", "completed", "error", "cancelled"
     pub speed: Option<f64>, // 下载速度 KB/s
+    pub url: Option<String>, // 完成时为实际使用的下载地址（可能是备用地址）
+}
+
+#[tauri::command]
+pub async fn fetch_trainers(
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    trainer_service::fetch_trainers(page, source).await
+}
+
+#[tauri::command]
+pub async fn search_trainers(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    trainer_service::search_trainers(query, page, source).await
+}
+
+#[tauri::command]
+pub async fn get_trainer_detail(id: String, source: Option<String>) -> AppResult<TrainerDetail> {
+    trainer_service::get_trainer_detail(id, source).await
+}
+
+/// 获取修改器的历史版本
+#[tauri::command]
+pub async fn get_trainer_revisions(
+    trainer_id: String,
+    source: Option<String>,
+) -> AppResult<Vec<TrainerRevision>> {
+    revision::list(trainer_id, source).await
+}
+
+/// 获取两个版本之间的更新记录，未指定版本时比较最近两次
+#[tauri::command]
+pub async fn get_trainer_changelog(
+    trainer_id: String,
+    source: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> AppResult<TrainerChangelog> {
+    revision::changelog(trainer_id, source, from, to).await
 }
 
+/// 重新加载选择器规则，返回生效的规则版本
 #[tauri::command]
-pub async fn fetch_trainers(page: u32) -> AppResult<PaginatedResponse<Trainer>> {
-    trainer_service::fetch_trainers(page).await
+pub fn reload_scraper_rules() -> AppResult<u32> {
+    scraper_rules::load()
 }
 
+/// 抓取器健康检查，live 为 false 时使用内置页面样本
 #[tauri::command]
-pub async fn search_trainers(query: String, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
-    trainer_service::search_trainers(query, page).await
+pub async fn check_scraper_health(live: bool) -> AppResult<HealthReport> {
+    if live {
+        scraper_health::check_live().await
+    } else {
+        Ok(scraper_health::check_fixtures())
+    }
 }
 
 #[tauri::command]
-pub async fn get_trainer_detail(id: String) -> AppResult<Trainer> {
-    trainer_service::get_trainer_detail(id).await
+pub fn list_sources() -> AppResult<Vec<SourceInfo>> {
+    sources::list_sources()
 }
 
 #[tauri::command]
 pub async fn download_trainer<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     trainer: Trainer,
+    variant: Option<usize>,
+    priority: Option<i32>,
 ) -> AppResult<PathBuf> {
-    trainer_service::download_trainer(app_handle, trainer).await
+    trainer_service::download_trainer(app_handle, trainer, variant, priority.unwrap_or(0)).await
 }
 
 #[tauri::command]
-pub async fn delete_trainer(trainer_id: String) -> AppResult<()> {
-    trainer_service::delete_trainer(trainer_id).await
+pub async fn delete_trainer(trainer_id: String, source: Option<String>) -> AppResult<()> {
+    trainer_service::delete_trainer(trainer_id, source).await
 }
 
 #[tauri::command]
-pub async fn launch_trainer(trainer_id: String) -> AppResult<()> {
-    trainer_service::launch_trainer(trainer_id).await
+pub async fn launch_trainer(trainer_id: String, source: Option<String>) -> AppResult<()> {
+    trainer_service::launch_trainer(trainer_id, source).await
 }
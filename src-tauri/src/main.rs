@@ -26,12 +26,47 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        // 缩略图协议，优先读取本地缓存
+        .register_asynchronous_uri_scheme_protocol(
+            services::image_cache::SCHEME,
+            |_ctx, request, responder| {
+                let path = request.uri().path().to_string();
+                let query = request.uri().query().map(str::to_string);
+                tauri::async_runtime::spawn(async move {
+                    responder.respond(
+                        services::image_cache::handle_request(&path, query.as_deref()).await,
+                    );
+                });
+            },
+        )
         .setup(|app| {
             // 初始化 SQLite 存储，失败时仅打印警告以避免阻塞启动
             if let Err(e) = tauri::async_runtime::block_on(services::storage::init_db()) {
                 println!("警告: 无法初始化本地数据库: {}", e);
             }
 
+            // 恢复上次退出时未完成的下载
+            if let Err(e) = tauri::async_runtime::block_on(services::trainer::restore_downloads(
+                app.handle().clone(),
+            )) {
+                println!("警告: 无法恢复下载队列: {}", e);
+            }
+
+            // 加载并校验选择器规则，覆盖文件无效时回退到内置规则
+            if let Err(e) = services::scraper_rules::load() {
+                println!("警告: 选择器规则无效，已使用内置规则: {}", e);
+            }
+
+            // 启动时在后台增量同步修改器目录
+            let auto_sync = services::settings::load_settings()
+                .map(|s| s.auto_sync_catalog)
+                .unwrap_or(false);
+            if auto_sync {
+                if let Err(e) = services::catalog::start_sync(app.handle().clone(), false) {
+                    println!("警告: 无法启动目录同步: {}", e);
+                }
+            }
+
             // 创建系统托盘
             let show_item = MenuItemBuilder::with_id("show", "显示主窗口").build(app)?;
             let hide_item = MenuItemBuilder::with_id("hide", "隐藏主窗口").build(app)?;
@@ -90,6 +125,21 @@ fn main() {
             api::trainer::download_trainer,
             api::trainer::delete_trainer,
             api::trainer::launch_trainer,
+            api::trainer::list_sources,
+            api::trainer::reload_scraper_rules,
+            api::trainer::check_scraper_health,
+            api::trainer::get_trainer_revisions,
+            api::trainer::get_trainer_changelog,
+            // 本地目录API
+            api::catalog::start_catalog_sync,
+            api::catalog::get_catalog_status,
+            api::catalog::get_catalog_trainers,
+            api::catalog::search_local,
+            // 游戏别名API
+            api::alias::list_aliases,
+            api::alias::save_alias,
+            api::alias::delete_alias,
+            api::alias::import_aliases,
             // 更新相关API
             api::updater::check_update,
             api::updater::download_and_install_update,
@@ -99,6 +149,8 @@ fn main() {
             // 设置相关API
             api::settings::get_settings,
             api::settings::save_settings,
+            api::settings::test_proxy,
+            api::settings::get_network_status,
             api::settings::get_download_path,
             api::settings::set_download_path,
             api::settings::select_download_folder,
@@ -119,6 +171,10 @@ fn main() {
             // 下载管理器API
             services::download_manager::get_active_downloads,
             services::download_manager::cancel_download,
+            services::download_manager::pause_download,
+            services::download_manager::resume_download,
+            services::download_manager::set_download_priority,
+            services::download_manager::move_download,
             services::download_manager::clear_all_downloads,
             services::download_manager::get_download_directory,
             services::download_manager::open_download_directory,
@@ -0,0 +1,29 @@
+use crate::models::trainer::default_source;
+use serde::{Deserialize, Serialize};
+
+/// 游戏别名，例如中文译名或常用缩写
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GameAlias {
+    pub alias: String,
+    /// 对应的目录条目 ID
+    pub trainer_id: String,
+    #[serde(default = "default_source")]
+    pub source: String,
+    /// 别名语言，例如 "zh-CN"；为空表示通用（如英文缩写）
+    #[serde(default)]
+    pub language: String,
+}
+
+impl GameAlias {
+    /// 匹配用的键：小写并去掉空白和标点
+    pub fn key(&self) -> String {
+        normalize_alias(&self.alias)
+    }
+}
+
+pub fn normalize_alias(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -1,3 +1,3 @@
+pub mod alias;
+pub mod revision;
 pub mod trainer;
-
- 
\ No newline at end of file
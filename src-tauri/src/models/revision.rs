@@ -0,0 +1,43 @@
+use crate::models::trainer::{TrainerDate, TrainerOption};
+use serde::{Deserialize, Serialize};
+
+/// 修改器的一次抓取版本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrainerRevision {
+    pub id: i64,
+    pub source: String,
+    pub trainer_id: String,
+    pub version: String,
+    pub game_version: String,
+    pub last_update: TrainerDate,
+    pub options: Vec<TrainerOption>,
+    /// 记录时间（RFC3339）
+    pub recorded_at: String,
+}
+
+/// 字段变化（旧值 -> 新值）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    pub from: String,
+    pub to: String,
+}
+
+/// 同一功能的热键变化
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HotkeyChange {
+    pub label: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// 两个版本之间的更新记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrainerChangelog {
+    pub from: TrainerRevision,
+    pub to: TrainerRevision,
+    pub version_change: Option<ValueChange>,
+    pub game_version_change: Option<ValueChange>,
+    pub options_added: Vec<TrainerOption>,
+    pub options_removed: Vec<TrainerOption>,
+    pub hotkey_changes: Vec<HotkeyChange>,
+}
@@ -1,4 +1,7 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Trainer {
@@ -10,7 +13,146 @@ pub struct Trainer {
     pub description: String,
     pub thumbnail: String,
     pub download_count: i32,
-    pub last_update: String,
+    pub last_update: TrainerDate,
+    /// 来源标识，例如 "fling"
+    #[serde(default = "default_source")]
+    pub source: String,
+    /// 功能列表（热键 + 说明）
+    #[serde(default)]
+    pub options: Vec<TrainerOption>,
+    /// 可选下载项（不同游戏版本或平台）
+    #[serde(default)]
+    pub downloads: Vec<TrainerDownload>,
+    /// 备用下载地址（例如团队镜像），`download_url` 失败后按顺序尝试
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// 下载文件的 SHA-256（小写十六进制），来源提供时下载后校验
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// 修改器功能项，例如 "Num 1 – Infinite Health"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TrainerOption {
+    pub hotkey: String,
+    pub label: String,
+    /// 所属分组标题，没有分组时为空
+    pub group: Option<String>,
+}
+
+/// 修改器下载附件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrainerDownload {
+    pub label: String,
+    pub url: String,
+    pub size: String,
+    pub date: String,
+    pub download_count: i32,
+    /// 附件的备用下载地址
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// 附件的 SHA-256，来源提供时下载后校验
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// 更新日期，统一为 ISO 格式（YYYY-MM-DD），无法识别时保留原文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrainerDate {
+    Date(NaiveDate),
+    Raw(String),
+}
+
+// 网站上出现过的日期格式
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y.%m.%d",
+    "%Y/%m/%d",
+    "%d %b %Y",
+    "%d %B %Y",
+    "%b %d, %Y",
+    "%B %d, %Y",
+];
+
+impl TrainerDate {
+    pub fn parse(text: &str) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = text.trim_start_matches("Last Updated:").trim();
+
+        if let Some(date) = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+        {
+            return TrainerDate::Date(date);
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+            return TrainerDate::Date(datetime.date_naive());
+        }
+        TrainerDate::Raw(text.to_string())
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        match self {
+            TrainerDate::Date(date) => Some(*date),
+            TrainerDate::Raw(_) => None,
+        }
+    }
+}
+
+impl Default for TrainerDate {
+    fn default() -> Self {
+        TrainerDate::Raw(String::new())
+    }
+}
+
+impl fmt::Display for TrainerDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrainerDate::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            TrainerDate::Raw(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+// 按日期排序，无法识别的日期视为最旧
+impl Ord for TrainerDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (TrainerDate::Date(a), TrainerDate::Date(b)) => a.cmp(b),
+            (TrainerDate::Date(_), TrainerDate::Raw(_)) => Ordering::Greater,
+            (TrainerDate::Raw(_), TrainerDate::Date(_)) => Ordering::Less,
+            (TrainerDate::Raw(a), TrainerDate::Raw(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for TrainerDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// 前端与缓存中仍以字符串形式传递
+impl Serialize for TrainerDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TrainerDate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(|text| TrainerDate::parse(&text))
+    }
+}
+
+pub fn default_source() -> String {
+    crate::services::sources::DEFAULT_SOURCE.to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -19,6 +161,9 @@ pub struct TrainerInstallInfo {
     pub install_path: String,
     pub install_time: String,
     pub last_launch_time: Option<String>,
+    /// 安装包实际的 SHA-256
+    #[serde(default)]
+    pub installed_sha256: Option<String>,
 } 
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,8 +176,42 @@ pub struct InstalledTrainer {
     pub description: String,
     pub thumbnail: String,
     pub download_count: i32,
-    pub last_update: String,
+    pub last_update: TrainerDate,
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default)]
+    pub options: Vec<TrainerOption>,
     pub installed_path: String,
     pub install_time: String,
     pub last_launch_time: String,
-} 
\ No newline at end of file
+    /// 安装包实际的 SHA-256，旧版本安装的记录为空
+    #[serde(default)]
+    pub installed_sha256: Option<String>,
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_and_detail_dates_normalize_to_iso() {
+        // 列表页由日、月、年三个元素拼接
+        assert_eq!(TrainerDate::parse("12 Mar 2024").to_string(), "2024-03-12");
+        // 详情页带前缀
+        assert_eq!(
+            TrainerDate::parse("Last Updated: 2024.03.12").to_string(),
+            "2024-03-12"
+        );
+        assert_eq!(TrainerDate::parse("March 12, 2024").to_string(), "2024-03-12");
+    }
+
+    #[test]
+    fn unknown_dates_keep_raw_text_and_sort_oldest() {
+        let raw = TrainerDate::parse("  sometime  soon ");
+        assert_eq!(raw, TrainerDate::Raw("sometime soon".to_string()));
+        assert_eq!(raw.date(), None);
+        assert!(TrainerDate::parse("2020-01-01") > raw);
+
+        let json = serde_json::to_string(&TrainerDate::parse("12 Mar 2024")).unwrap();
+        assert_eq!(json, "\"2024-03-12\"");
+    }
+}
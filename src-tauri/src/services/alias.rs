@@ -0,0 +1,202 @@
+use crate::api::error::{AppError, AppResult};
+use crate::models::alias::{normalize_alias, GameAlias};
+use crate::models::trainer::default_source;
+use crate::services::settings;
+use crate::services::storage;
+use log::warn;
+use std::fs;
+use std::path::Path;
+
+/// 别名指向的目录条目
+#[derive(Debug, Clone)]
+pub struct AliasTarget {
+    pub source: String,
+    /// 目录中的英文名称，未同步时由 ID 推断
+    pub name: String,
+}
+
+fn db_error(e: anyhow::Error) -> AppError {
+    AppError::ExecutionError(format!("别名数据库操作失败: {}", e))
+}
+
+fn validate(alias: &GameAlias) -> AppResult<()> {
+    if alias.key().is_empty() {
+        return Err(AppError::ValidationError(format!("别名无效: {}", alias.alias)));
+    }
+    if alias.trainer_id.trim().is_empty() {
+        return Err(AppError::ValidationError(format!(
+            "别名 {} 缺少目标 ID",
+            alias.alias
+        )));
+    }
+    Ok(())
+}
+
+// 目录 ID 形如 "elden-ring"，还原为可搜索的名称
+fn name_from_id(id: &str) -> String {
+    id.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 按当前语言设置查找查询对应的条目
+pub async fn lookup(query: &str) -> AppResult<Vec<AliasTarget>> {
+    let key = normalize_alias(query);
+    if key.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let language = settings::load_settings()?.language;
+    let targets = storage::find_alias_targets(key, language)
+        .await
+        .map_err(db_error)?;
+
+    Ok(targets
+        .into_iter()
+        .map(|(source, trainer_id, name)| AliasTarget {
+            name: name.unwrap_or_else(|| name_from_id(&trainer_id)),
+            source,
+        })
+        .collect())
+}
+
+/// 远程搜索前将别名替换为英文名称，没有匹配时返回原查询
+pub async fn translate_query(query: &str) -> String {
+    match lookup(query).await {
+        Ok(targets) => targets
+            .into_iter()
+            .next()
+            .map(|target| target.name)
+            .unwrap_or_else(|| query.to_string()),
+        Err(e) => {
+            warn!("查询别名失败: {}", e);
+            query.to_string()
+        }
+    }
+}
+
+pub async fn list(trainer_id: Option<String>) -> AppResult<Vec<GameAlias>> {
+    storage::get_aliases(trainer_id).await.map_err(db_error)
+}
+
+pub async fn save(alias: GameAlias) -> AppResult<()> {
+    validate(&alias)?;
+    storage::upsert_aliases(vec![alias]).await.map_err(db_error)?;
+    Ok(())
+}
+
+pub async fn remove(alias: GameAlias) -> AppResult<()> {
+    storage::remove_alias(alias).await.map_err(db_error)
+}
+
+/// 从 CSV 或 JSON 文件导入别名，返回导入条数
+pub async fn import_file(path: &Path) -> AppResult<u32> {
+    let content = fs::read_to_string(path)?;
+    let aliases = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => parse_csv(&content)?,
+        Some(ext) if ext.eq_ignore_ascii_case("json") => parse_json(&content)?,
+        _ => {
+            return Err(AppError::ValidationError(format!(
+                "仅支持 CSV 或 JSON 文件: {}",
+                path.display()
+            )))
+        }
+    };
+
+    for alias in &aliases {
+        validate(alias)?;
+    }
+    let count = storage::upsert_aliases(aliases).await.map_err(db_error)?;
+    Ok(count as u32)
+}
+
+/// 解析 JSON 别名列表
+pub fn parse_json(content: &str) -> AppResult<Vec<GameAlias>> {
+    Ok(serde_json::from_str(content)?)
+}
+
+/// 解析 CSV 别名列表，列顺序为 alias,trainer_id[,source][,language]，表头可选
+pub fn parse_csv(content: &str) -> AppResult<Vec<GameAlias>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut aliases = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(line);
+        if index == 0 && fields[0].eq_ignore_ascii_case("alias") {
+            continue;
+        }
+        if fields.len() < 2 {
+            return Err(AppError::ParseError(format!(
+                "别名文件第 {} 行缺少列: {}",
+                index + 1,
+                line
+            )));
+        }
+
+        let field = |i: usize| fields.get(i).map(|f| f.trim().to_string()).unwrap_or_default();
+        let source = field(2);
+        aliases.push(GameAlias {
+            alias: field(0),
+            trainer_id: field(1),
+            source: if source.is_empty() { default_source() } else { source },
+            language: field(3),
+        });
+    }
+
+    Ok(aliases)
+}
+
+// 拆分一行 CSV，支持双引号包裹和 "" 转义
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_with_header_and_quotes() {
+        let content = "\u{feff}alias,trainer_id,source,language\n\
+                       老头环,elden-ring,,zh-CN\n\
+                       \"RE4, Remake\",resident-evil-4-remake,fling,\n\
+                       \n\
+                       \"Say \"\"Hi\"\"\",say-hi\n";
+        let aliases = parse_csv(content).unwrap();
+
+        assert_eq!(aliases.len(), 3);
+        assert_eq!(aliases[0].alias, "老头环");
+        assert_eq!(aliases[0].source, "fling");
+        assert_eq!(aliases[0].language, "zh-CN");
+        assert_eq!(aliases[1].alias, "RE4, Remake");
+        assert_eq!(aliases[1].key(), "re4remake");
+        assert_eq!(aliases[2].alias, "Say \"Hi\"");
+        assert_eq!(aliases[2].language, "");
+    }
+
+    #[test]
+    fn csv_rejects_missing_columns() {
+        assert!(parse_csv("老头环\n").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::services::settings::{self, BandwidthSettings};
+use chrono::{Local, NaiveTime};
+use lazy_static::lazy_static;
+use log::info;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    static ref LIMITS: RwLock<BandwidthSettings> = RwLock::new(
+        settings::load_settings()
+            .map(|s| s.bandwidth)
+            .unwrap_or_default()
+    );
+    // 所有下载共用的令牌桶
+    static ref GLOBAL_BUCKET: Mutex<TokenBucket> = Mutex::new(TokenBucket::new(0));
+}
+
+/// 令牌桶，每秒补充 `rate` 个字节，最多积攒一秒的量
+///
+/// 取出的量可以超过桶中剩余的令牌，差额通过等待补足。
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// `rate` 为每秒字节数，0 表示不限速
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn set_rate(&mut self, rate: u64) {
+        if self.rate != rate {
+            self.rate = rate;
+            self.tokens = self.tokens.min(rate as f64);
+        }
+    }
+
+    /// 取出 `amount` 个令牌，返回需要等待的时间
+    pub fn take(&mut self, amount: u64, now: Instant) -> Duration {
+        if self.rate == 0 {
+            return Duration::ZERO;
+        }
+
+        let capacity = self.rate as f64;
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity).min(capacity) - amount as f64;
+        self.last_refill = now;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / capacity)
+        }
+    }
+}
+
+/// 设置变更后更新限速
+pub fn apply(limits: &BandwidthSettings) {
+    *LIMITS.write().unwrap() = limits.clone();
+    info!(
+        "已应用下载限速: 全局 {} KB/s，单个下载 {} KB/s",
+        limits.limit_kbps, limits.per_download_limit_kbps
+    );
+}
+
+// 判断时间是否在时段内，结束时间早于开始时间时表示跨过午夜
+fn in_window(now: NaiveTime, from: NaiveTime, to: NaiveTime) -> bool {
+    if from <= to {
+        from <= now && now < to
+    } else {
+        now >= from || now < to
+    }
+}
+
+// 当前生效的限速（全局, 单个下载），单位为字节每秒
+fn current_limits() -> (u64, u64) {
+    let limits = LIMITS.read().unwrap();
+    if limits.unlimited_schedule {
+        let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").ok();
+        if let (Some(from), Some(to)) = (parse(&limits.unlimited_from), parse(&limits.unlimited_to)) {
+            if in_window(Local::now().time(), from, to) {
+                return (0, 0);
+            }
+        }
+    }
+    (limits.limit_kbps * 1024, limits.per_download_limit_kbps * 1024)
+}
+
+/// 按全局和单个下载的限速取出令牌，返回需要等待的时间
+///
+/// `download` 为该下载自己的令牌桶，分段下载的各个连接共用同一个。
+pub fn reserve(download: &mut TokenBucket, bytes: u64) -> Duration {
+    let (global_rate, download_rate) = current_limits();
+    let now = Instant::now();
+
+    let global_wait = {
+        let mut global = GLOBAL_BUCKET.lock().unwrap();
+        global.set_rate(global_rate);
+        global.take(bytes, now)
+    };
+    download.set_rate(download_rate);
+    global_wait.max(download.take(bytes, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_delays() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000);
+        bucket.last_refill = start;
+
+        assert_eq!(bucket.take(1000, start), Duration::ZERO);
+        assert_eq!(bucket.take(500, start), Duration::from_millis(500));
+        // 半秒后补充的令牌刚好抵消欠下的量
+        assert_eq!(bucket.take(0, start + Duration::from_millis(500)), Duration::ZERO);
+        assert_eq!(TokenBucket::new(0).take(1 << 20, start), Duration::ZERO);
+    }
+
+    #[test]
+    fn window_wraps_past_midnight() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(in_window(time(23, 30), time(23, 0), time(7, 0)));
+        assert!(in_window(time(6, 59), time(23, 0), time(7, 0)));
+        assert!(!in_window(time(12, 0), time(23, 0), time(7, 0)));
+        assert!(in_window(time(13, 0), time(12, 0), time(14, 0)));
+        assert!(!in_window(time(14, 0), time(12, 0), time(14, 0)));
+    }
+}
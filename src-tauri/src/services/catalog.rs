@@ -0,0 +1,516 @@
+use crate::api::error::{AppError, AppResult};
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::{Trainer, TrainerDate};
+use crate::services::alias;
+use crate::services::image_cache;
+use crate::services::offline;
+use crate::services::sources::{self, TrainerSource};
+use crate::services::storage::{self, CatalogSyncState};
+use crate::services::trainer as trainer_service;
+use chrono::{NaiveDate, Utc};
+use futures_util::future::join_all;
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
+
+// 同时抓取的列表页数量
+const SYNC_CONCURRENCY: usize = 4;
+// 本地目录每页条数
+pub const CATALOG_PAGE_SIZE: u32 = 20;
+// 同步进度事件名
+pub const SYNC_PROGRESS_EVENT: &str = "catalog-sync-progress";
+
+// 同一时间只允许一个同步任务
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+/// 同步进度
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    pub source: String,
+    /// "syncing" | "completed" | "error"
+    pub status: String,
+    pub pages_done: u32,
+    pub total_pages: u32,
+    /// 本次写入的条目数
+    pub items: u32,
+    pub failed_pages: u32,
+    pub error: Option<String>,
+}
+
+/// 本地目录状态
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogStatus {
+    pub syncing: bool,
+    pub sources: Vec<CatalogSyncState>,
+}
+
+fn db_error(e: anyhow::Error) -> AppError {
+    AppError::ExecutionError(format!("目录数据库操作失败: {}", e))
+}
+
+/// 在后台启动目录同步
+///
+/// `full` 为 false 时只抓取上次同步之后更新的页面。
+pub fn start_sync<R: Runtime>(app_handle: AppHandle<R>, full: bool) -> AppResult<()> {
+    if offline::is_forced() {
+        return Err(AppError::OfflineError("无法同步目录".to_string()));
+    }
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Err(AppError::ValidationError("目录同步正在进行中".to_string()));
+    }
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = sync_all(&app_handle, full).await {
+            warn!("目录同步失败: {}", e);
+        }
+        SYNCING.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+async fn sync_all<R: Runtime>(app_handle: &AppHandle<R>, full: bool) -> AppResult<()> {
+    for source in sources::all_sources()? {
+        if let Err(e) = sync_source(app_handle, source.as_ref(), full).await {
+            warn!("来源 {} 目录同步失败: {}", source.id(), e);
+            let _ = app_handle.emit(
+                SYNC_PROGRESS_EVENT,
+                SyncProgress {
+                    source: source.id().to_string(),
+                    status: "error".to_string(),
+                    pages_done: 0,
+                    total_pages: 0,
+                    items: 0,
+                    failed_pages: 0,
+                    error: Some(e.to_string()),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+// 增量同步的停止边界；列表不按更新时间排序的来源无法提前停止，每次全量同步
+fn sync_cutoff(sorted_by_date: bool, previous_newest: &TrainerDate) -> Option<NaiveDate> {
+    previous_newest.date().filter(|_| sorted_by_date)
+}
+
+// 列表按更新时间倒序排列，出现早于上次同步的条目说明后续页面都已同步过
+fn reached_cutoff(trainers: &[Trainer], cutoff: Option<NaiveDate>) -> bool {
+    cutoff.is_some_and(|cutoff| {
+        trainers
+            .iter()
+            .filter_map(|t| t.last_update.date())
+            .any(|date| date < cutoff)
+    })
+}
+
+async fn sync_source<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    source: &dyn TrainerSource,
+    full: bool,
+) -> AppResult<()> {
+    let previous = if full {
+        None
+    } else {
+        storage::get_catalog_sync_state(source.id())
+            .await
+            .map_err(db_error)?
+    };
+    let previous_newest = previous
+        .map(|state| state.newest_update)
+        .unwrap_or_default();
+    let cutoff = sync_cutoff(source.sorted_by_date(), &previous_newest);
+
+    let first = source.sync_list(1).await?;
+    let total_pages = first.total_pages.max(1);
+    let mut newest = previous_newest.clone();
+    let mut progress = SyncProgress {
+        source: source.id().to_string(),
+        status: "syncing".to_string(),
+        pages_done: 0,
+        total_pages,
+        items: 0,
+        failed_pages: 0,
+        error: None,
+    };
+
+    let mut stop = store_page(first.trainers, cutoff, &mut newest, &mut progress).await?;
+    let _ = app_handle.emit(SYNC_PROGRESS_EVENT, progress.clone());
+
+    let mut next_page = 2;
+    while !stop && next_page <= total_pages {
+        let batch: Vec<u32> = (next_page..=total_pages).take(SYNC_CONCURRENCY).collect();
+        next_page += batch.len() as u32;
+
+        let results = join_all(batch.iter().map(|&page| source.sync_list(page))).await;
+        for (page, result) in batch.iter().zip(results) {
+            match result {
+                Ok(response) => {
+                    stop |= store_page(response.trainers, cutoff, &mut newest, &mut progress).await?;
+                }
+                Err(e) => {
+                    warn!("来源 {} 第 {} 页同步失败: {}", source.id(), page, e);
+                    progress.failed_pages += 1;
+                }
+            }
+        }
+        let _ = app_handle.emit(SYNC_PROGRESS_EVENT, progress.clone());
+    }
+
+    // 有页面失败时不推进同步边界，下次同步会重新覆盖这些页面
+    let newest_update = if progress.failed_pages == 0 {
+        newest
+    } else {
+        previous_newest
+    };
+    storage::save_catalog_sync_state(CatalogSyncState {
+        source: source.id().to_string(),
+        last_sync_at: Utc::now().timestamp_millis(),
+        newest_update,
+        total_pages,
+        item_count: storage::count_catalog(Some(source.id())).await.map_err(db_error)?,
+    })
+    .await
+    .map_err(db_error)?;
+
+    info!(
+        "来源 {} 目录同步完成: {} 页, {} 条, 失败 {} 页",
+        source.id(),
+        progress.pages_done,
+        progress.items,
+        progress.failed_pages
+    );
+    progress.status = "completed".to_string();
+    let _ = app_handle.emit(SYNC_PROGRESS_EVENT, progress);
+    Ok(())
+}
+
+// 写入一页数据，返回是否已到达上次同步的边界
+async fn store_page(
+    trainers: Vec<Trainer>,
+    cutoff: Option<NaiveDate>,
+    newest: &mut TrainerDate,
+    progress: &mut SyncProgress,
+) -> AppResult<bool> {
+    let reached = reached_cutoff(&trainers, cutoff);
+    if let Some(latest) = trainers.iter().map(|t| &t.last_update).max() {
+        if latest > newest {
+            *newest = latest.clone();
+        }
+    }
+
+    // 同步的页面不经过列表接口，缩略图在这里预取
+    image_cache::prefetch(&trainers);
+    let count = storage::upsert_catalog_trainers(trainers)
+        .await
+        .map_err(db_error)?;
+    progress.pages_done += 1;
+    progress.items += count as u32;
+    Ok(reached)
+}
+
+/// 从本地目录分页读取修改器，不访问网络
+pub async fn get_catalog_trainers(
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let page = page.max(1);
+    let (trainers, total) = storage::get_catalog_trainers(source, page, CATALOG_PAGE_SIZE)
+        .await
+        .map_err(db_error)?;
+    let total_pages = total.div_ceil(CATALOG_PAGE_SIZE).max(1);
+
+    Ok(PaginatedResponse {
+        trainers,
+        total,
+        total_pages,
+        current_page: page,
+        has_next: page < total_pages,
+        stale: false,
+    })
+}
+
+// 将查询拆分为小写词，标点和空白都视为分隔符
+fn tokenize(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// 每个词做前缀匹配，词之间为 AND 关系
+fn match_expression(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("\"{}\"*", term))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 原查询与别名对应的名称之间为 OR 关系，名称按完整词匹配
+fn search_expression(terms: &[String], alias_names: &[String]) -> String {
+    let mut groups = vec![match_expression(terms)];
+    groups.extend(
+        alias_names
+            .iter()
+            .map(|name| {
+                tokenize(name)
+                    .iter()
+                    .map(|term| format!("\"{}\"", term))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .filter(|group| !group.is_empty()),
+    );
+
+    if groups.len() == 1 {
+        return groups.remove(0);
+    }
+    groups
+        .iter()
+        .map(|group| format!("({})", group))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+// 按字符计算编辑距离
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                previous.min(current).min(row[j]) + 1
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+// 允许的拼写错误数量，短词不做纠正
+fn max_typos(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+// 将索引中不存在的词替换为词表中最接近的词（按编辑距离，其次按出现次数）
+fn correct_terms(terms: &[String], vocab: &[(String, u32)]) -> Vec<String> {
+    terms
+        .iter()
+        .map(|term| {
+            let limit = max_typos(term);
+            if limit == 0 || vocab.iter().any(|(word, _)| word.starts_with(term.as_str())) {
+                return term.clone();
+            }
+
+            let length = term.chars().count();
+            vocab
+                .iter()
+                .filter_map(|(word, docs)| {
+                    // 同时和完整词、相近长度的前缀比较，兼容输入到一半的词
+                    let distance = [length, length + 1]
+                        .iter()
+                        .map(|&n| edit_distance(term, &word.chars().take(n).collect::<String>()))
+                        .fold(edit_distance(term, word), usize::min);
+                    (distance <= limit).then_some((distance, *docs, word))
+                })
+                .min_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+                .map(|(_, _, word)| word.clone())
+                .unwrap_or_else(|| term.clone())
+        })
+        .collect()
+}
+
+/// 在本地目录中搜索，支持前缀匹配、别名和拼写纠正
+///
+/// 本地目录为空时回退到远程搜索。
+pub async fn search_local(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let indexed = storage::count_catalog(source.as_deref())
+        .await
+        .map_err(db_error)?;
+    if indexed == 0 {
+        return trainer_service::search_trainers(query, page, source).await;
+    }
+    search_indexed(query, page, source).await
+}
+
+/// 只在本地目录中搜索，不访问网络
+pub async fn search_indexed(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let terms = tokenize(&query);
+    if terms.is_empty() {
+        return get_catalog_trainers(page, source).await;
+    }
+
+    // 中文译名、缩写等别名同时匹配对应的英文名称
+    let alias_names: Vec<String> = match alias::lookup(&query).await {
+        Ok(targets) => targets
+            .into_iter()
+            .filter(|target| source.as_ref().map_or(true, |id| *id == target.source))
+            .map(|target| target.name)
+            .collect(),
+        Err(e) => {
+            warn!("查询别名失败: {}", e);
+            Vec::new()
+        }
+    };
+
+    let page = page.max(1);
+    let (mut trainers, mut total) = storage::search_catalog(
+        source.clone(),
+        search_expression(&terms, &alias_names),
+        page,
+        CATALOG_PAGE_SIZE,
+    )
+    .await
+    .map_err(db_error)?;
+
+    // 没有结果时尝试纠正拼写后重新搜索
+    if total == 0 {
+        let vocab = storage::get_catalog_terms().await.map_err(db_error)?;
+        let corrected = correct_terms(&terms, &vocab);
+        if corrected != terms {
+            (trainers, total) = storage::search_catalog(
+                source,
+                search_expression(&corrected, &alias_names),
+                page,
+                CATALOG_PAGE_SIZE,
+            )
+            .await
+            .map_err(db_error)?;
+        }
+    }
+
+    let total_pages = total.div_ceil(CATALOG_PAGE_SIZE).max(1);
+    Ok(PaginatedResponse {
+        trainers,
+        total,
+        total_pages,
+        current_page: page,
+        has_next: page < total_pages,
+        stale: false,
+    })
+}
+
+/// 获取各来源的同步状态
+pub async fn get_status() -> AppResult<CatalogStatus> {
+    let mut states = Vec::new();
+    for source in sources::all_sources()? {
+        if let Some(state) = storage::get_catalog_sync_state(source.id())
+            .await
+            .map_err(db_error)?
+        {
+            states.push(state);
+        }
+    }
+
+    Ok(CatalogStatus {
+        syncing: SYNCING.load(Ordering::SeqCst),
+        sources: states,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trainer_on(date: &str) -> Trainer {
+        Trainer {
+            id: date.to_string(),
+            name: String::new(),
+            version: String::new(),
+            game_version: String::new(),
+            download_url: String::new(),
+            description: String::new(),
+            thumbnail: String::new(),
+            download_count: 0,
+            last_update: TrainerDate::parse(date),
+            source: "fling".to_string(),
+            options: Vec::new(),
+            downloads: Vec::new(),
+            mirrors: Vec::new(),
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn cutoff_stops_on_older_entries() {
+        let cutoff = NaiveDate::from_ymd_opt(2024, 3, 10);
+        let newer = [trainer_on("2024-03-12"), trainer_on("2024-03-10")];
+        let mixed = [trainer_on("2024-03-11"), trainer_on("2024-03-09")];
+
+        assert!(!reached_cutoff(&newer, cutoff));
+        assert!(reached_cutoff(&mixed, cutoff));
+        // 首次同步没有边界，抓取全部页面
+        assert!(!reached_cutoff(&mixed, None));
+        // 无法识别的日期不作为停止依据
+        assert!(!reached_cutoff(&[trainer_on("unknown")], cutoff));
+    }
+
+    #[test]
+    fn unsorted_sources_sync_every_page() {
+        let previous = TrainerDate::parse("2024-03-10");
+        // 文件顺序的索引：旧条目在前，更新过的条目在后面
+        let page = [trainer_on("2024-01-01"), trainer_on("2024-03-15")];
+
+        assert!(reached_cutoff(&page, sync_cutoff(true, &previous)));
+        assert_eq!(sync_cutoff(false, &previous), None);
+        assert!(!reached_cutoff(&page, sync_cutoff(false, &previous)));
+    }
+
+    #[test]
+    fn query_becomes_prefix_match() {
+        let terms = tokenize("Elden Ring: Shadow-of");
+        assert_eq!(terms, ["elden", "ring", "shadow", "of"]);
+        assert_eq!(
+            match_expression(&terms),
+            "\"elden\"* \"ring\"* \"shadow\"* \"of\"*"
+        );
+    }
+
+    #[test]
+    fn alias_names_are_alternatives() {
+        let terms = tokenize("老头环");
+        assert_eq!(search_expression(&terms, &[]), "\"老头环\"*");
+        assert_eq!(
+            search_expression(&terms, &["Elden Ring".to_string()]),
+            "(\"老头环\"*) OR (\"elden\" \"ring\")"
+        );
+    }
+
+    #[test]
+    fn misspelled_terms_are_corrected() {
+        let vocab = vec![
+            ("cyberpunk".to_string(), 3),
+            ("cybercity".to_string(), 1),
+            ("2077".to_string(), 1),
+            ("ring".to_string(), 5),
+        ];
+        let terms = vec!["cyberpnk".to_string(), "2077".to_string()];
+        assert_eq!(correct_terms(&terms, &vocab), ["cyberpunk", "2077"]);
+
+        // 输入到一半的词也能纠正
+        assert_eq!(correct_terms(&["cybrpu".to_string()], &vocab), ["cyberpunk"]);
+        // 短词不纠正
+        assert_eq!(correct_terms(&["rin".to_string()], &vocab), ["rin"]);
+    }
+}
@@ -1,178 +1,1324 @@
 // 导入的引用
 use crate::api::error::{AppError, AppResult};
 use crate::api::trainer::DownloadProgress;
-use crate::services::settings;
+use crate::services::bandwidth::{self, TokenBucket};
+use crate::services::http;
+use crate::services::settings::{self, AppSettings};
+use crate::services::storage::{self, DownloadQueueRecord};
+use futures_util::future::try_join_all;
 use futures_util::StreamExt;
 use lazy_static::lazy_static;
+use log::{info, warn};
+use reqwest::header::{ACCEPT_RANGES, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Response, StatusCode};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
 use std::collections::HashMap;
-use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use tauri::Emitter;
+use tokio::sync::Notify;
+
+// 每个分段至少的大小，文件太小时不分段
+const MIN_SEGMENT_SIZE: u64 = 4 * 1024 * 1024;
+// 单个分段失败后的最大重试次数
+const SEGMENT_MAX_RETRIES: u32 = 3;
+// 分段首次重试前的等待时间，之后每次翻倍
+const SEGMENT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// 下载控制状态
+const STATE_RUNNING: u8 = 0;
+const STATE_PAUSED: u8 = 1;
+const STATE_CANCELLED: u8 = 2;
 
 // 下载管理器状态
 lazy_static! {
     static ref ACTIVE_DOWNLOADS: Arc<Mutex<HashMap<String, DownloadProgress>>> =
         Arc::new(Mutex::new(HashMap::new()));
+    static ref DOWNLOAD_CONTROLS: Mutex<HashMap<String, Arc<DownloadControl>>> =
+        Mutex::new(HashMap::new());
+    // 下载队列，顺序即用户调整后的排队顺序
+    static ref DOWNLOAD_QUEUE: Mutex<Vec<QueueEntry>> = Mutex::new(Vec::new());
+    // 队列变化（完成、暂停、调整顺序、修改并发数）时唤醒等待槽位的下载
+    static ref QUEUE_CHANGED: Notify = Notify::new();
+    // 保存队列时持有，保证写入按顺序进行
+    static ref QUEUE_WRITE: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+    // 下载相关设置，避免排队和下载时反复读取设置文件
+    static ref QUEUE_SETTINGS: RwLock<QueueSettings> = RwLock::new(
+        QueueSettings::from(&settings::load_settings().unwrap_or_default())
+    );
+}
+
+// 下载队列使用的设置项
+#[derive(Debug, Clone)]
+struct QueueSettings {
+    max_parallel_downloads: usize,
+    segments: u32,
+    mirror_templates: Vec<String>,
+}
+
+impl From<&AppSettings> for QueueSettings {
+    fn from(app_settings: &AppSettings) -> Self {
+        Self {
+            max_parallel_downloads: app_settings.max_parallel_downloads.max(1) as usize,
+            segments: app_settings.download_segments,
+            mirror_templates: app_settings.download_mirror_templates.clone(),
+        }
+    }
+}
+
+/// 下载队列中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueState {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+}
+
+impl QueueState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QueueState::Queued => "queued",
+            QueueState::Running => "running",
+            QueueState::Paused => "paused",
+            QueueState::Failed => "failed",
+        }
+    }
+}
+
+struct QueueEntry {
+    file_id: String,
+    priority: i32,
+    state: QueueState,
+}
+
+/// 下载队列条目，包含进度和排队信息
+#[derive(Clone, Serialize)]
+pub struct QueuedDownload {
+    #[serde(flatten)]
+    pub progress: DownloadProgress,
+    pub state: QueueState,
+    pub priority: i32,
+}
+
+// 下载控制，下载循环在等待数据时同时等待暂停和取消
+struct DownloadControl {
+    state: AtomicU8,
+    notify: Notify,
+}
+
+impl DownloadControl {
+    fn state(&self) -> u8 {
+        self.state.load(Ordering::SeqCst)
+    }
+
+    // 从 `from` 切换到 `to`，当前状态不是 `from` 时返回 false
+    fn transition(&self, from: u8, to: u8) -> bool {
+        let changed = self
+            .state
+            .compare_exchange(from, to, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok();
+        if changed {
+            self.notify.notify_waiters();
+            QUEUE_CHANGED.notify_waiters();
+        }
+        changed
+    }
+
+    fn cancel(&self) {
+        self.state.store(STATE_CANCELLED, Ordering::SeqCst);
+        self.notify.notify_waiters();
+        QUEUE_CHANGED.notify_waiters();
+    }
+
+    // 等待状态离开 `state`，返回新的状态
+    async fn wait_while(&self, state: u8) -> u8 {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            // 先登记等待再检查状态，避免错过检查之后才发出的通知
+            notified.as_mut().enable();
+            let current = self.state();
+            if current != state {
+                return current;
+            }
+            notified.await;
+        }
+    }
+}
+
+// 下载结束（包括出错和取消）时注销控制，失败的条目保留在队列中
+struct ControlGuard {
+    file_id: String,
+}
+
+impl Drop for ControlGuard {
+    fn drop(&mut self) {
+        DOWNLOAD_CONTROLS.lock().unwrap().remove(&self.file_id);
+        DOWNLOAD_QUEUE
+            .lock()
+            .unwrap()
+            .retain(|e| e.file_id != self.file_id || e.state == QueueState::Failed);
+        QUEUE_CHANGED.notify_waiters();
+    }
 }
 
-// 获取所有活动下载的进度
+/// 设置修改后更新下载设置并重新检查队列（例如并发数变化）
+pub fn refresh_queue(app_settings: &AppSettings) {
+    *QUEUE_SETTINGS.write().unwrap() = QueueSettings::from(app_settings);
+    QUEUE_CHANGED.notify_waiters();
+}
+
+fn max_parallel_downloads() -> usize {
+    QUEUE_SETTINGS.read().unwrap().max_parallel_downloads
+}
+
+// 下一个可以开始的条目：优先级高的先开始，同优先级按队列顺序
+fn next_to_start(queue: &[QueueEntry], max_parallel: usize) -> Option<usize> {
+    let running = queue
+        .iter()
+        .filter(|e| e.state == QueueState::Running)
+        .count();
+    if running >= max_parallel {
+        return None;
+    }
+    queue
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.state == QueueState::Queued)
+        .max_by_key(|(index, e)| (e.priority, Reverse(*index)))
+        .map(|(index, _)| index)
+}
+
+// 轮到该下载且有空闲槽位时标记为运行中
+fn try_start(file_id: &str) -> bool {
+    let max_parallel = max_parallel_downloads();
+    let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
+    // 重新连接时已占有槽位
+    if queue
+        .iter()
+        .any(|e| e.file_id == file_id && e.state == QueueState::Running)
+    {
+        return true;
+    }
+    match next_to_start(&queue, max_parallel) {
+        Some(index) if queue[index].file_id == file_id => {
+            queue[index].state = QueueState::Running;
+            true
+        }
+        _ => false,
+    }
+}
+
+// 等待空闲的下载槽位，暂停或取消时返回 false
+async fn acquire_slot(file_id: &str, control: &DownloadControl) -> bool {
+    loop {
+        let notified = QUEUE_CHANGED.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if control.state() != STATE_RUNNING {
+            return false;
+        }
+        if try_start(file_id) {
+            return true;
+        }
+        notified.await;
+    }
+}
+
+fn set_queue_state(file_id: &str, state: QueueState) {
+    if let Some(entry) = DOWNLOAD_QUEUE
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .find(|e| e.file_id == file_id)
+    {
+        entry.state = state;
+    }
+    QUEUE_CHANGED.notify_waiters();
+    persist_queue();
+}
+
+fn cancelled_error(file_id: &str) -> AppError {
+    AppError::CancelledError(format!("下载已取消: {}", file_id))
+}
+
+fn progress_of(
+    file_id: &str,
+    downloaded: u64,
+    total_size: Option<u64>,
+    status: &str,
+    speed: Option<f64>,
+) -> DownloadProgress {
+    DownloadProgress {
+        trainer_id: file_id.to_string(),
+        progress: match total_size {
+            Some(total) if total > 0 => (downloaded as f64 / total as f64) * 100.0,
+            _ => 0.0, // 无法确定总大小
+        },
+        downloaded_bytes: downloaded,
+        total_bytes: total_size,
+        status: status.to_string(),
+        speed,
+        url: None,
+    }
+}
+
+// 更新活动下载列表并发送进度事件
+fn report<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    event_name: &str,
+    progress: DownloadProgress,
+) {
+    ACTIVE_DOWNLOADS
+        .lock()
+        .unwrap()
+        .insert(progress.trainer_id.clone(), progress.clone());
+    let _ = app_handle.emit(event_name, progress);
+}
+
+// 续传校验值，优先使用强 ETag，其次使用 Last-Modified
+fn resume_validator(response: &Response) -> Option<String> {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+// 从 `Content-Range: bytes start-end/total` 中读取文件总大小
+fn content_range_total(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+// 获取下载队列中所有条目的进度和状态，按排队顺序返回
 #[tauri::command]
-pub fn get_active_downloads() -> Vec<DownloadProgress> {
+pub fn get_active_downloads() -> Vec<QueuedDownload> {
+    let queue = DOWNLOAD_QUEUE.lock().unwrap();
     let downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-    downloads.values().cloned().collect()
+    queue
+        .iter()
+        .map(|entry| QueuedDownload {
+            progress: downloads
+                .get(&entry.file_id)
+                .cloned()
+                .unwrap_or_else(|| progress_of(&entry.file_id, 0, None, "queued", None)),
+            state: entry.state,
+            priority: entry.priority,
+        })
+        .collect()
+}
+
+/// 下载任务
+#[derive(Debug, Clone)]
+pub struct DownloadTask {
+    pub file_id: String,
+    pub url: String,
+    pub save_path: PathBuf,
+    pub event_name: String,
+    /// 越大越先开始
+    pub priority: i32,
+    /// 调用方附带的数据，随队列保存，重启后用于恢复任务
+    pub payload: String,
+    /// 加入队列时保持暂停，等待用户继续
+    pub paused: bool,
+    /// 续传校验值（ETag 或 Last-Modified），有值且存在部分文件时从断点继续
+    pub validator: Option<String>,
+    pub total_bytes: Option<u64>,
+    /// 上次保存的下载进度，恢复时与部分文件核对后从这里继续
+    pub downloaded_bytes: u64,
+    /// 期望的 SHA-256，有值时下载完成后校验（不随队列保存，恢复时由调用方重新设置）
+    pub expected_sha256: Option<String>,
+    /// 备用下载地址，`url` 失败后按顺序尝试（同上，不随队列保存）
+    pub mirrors: Vec<String>,
+}
+
+impl DownloadTask {
+    pub fn new(file_id: &str, url: &str, save_path: PathBuf, event_name: &str) -> Self {
+        Self {
+            file_id: file_id.to_string(),
+            url: url.to_string(),
+            save_path,
+            event_name: event_name.to_string(),
+            priority: 0,
+            payload: String::new(),
+            paused: false,
+            validator: None,
+            total_bytes: None,
+            downloaded_bytes: 0,
+            expected_sha256: None,
+            mirrors: Vec::new(),
+        }
+    }
+
+    fn record(&self, position: usize, state: QueueState) -> DownloadQueueRecord {
+        DownloadQueueRecord {
+            file_id: self.file_id.clone(),
+            url: self.url.clone(),
+            save_path: self.save_path.to_string_lossy().to_string(),
+            event_name: self.event_name.clone(),
+            priority: self.priority,
+            position: position as i64,
+            state: state.as_str().to_string(),
+            downloaded_bytes: self.downloaded_bytes,
+            total_bytes: self.total_bytes,
+            validator: self.validator.clone(),
+            payload: self.payload.clone(),
+        }
+    }
+}
+
+impl From<DownloadQueueRecord> for DownloadTask {
+    fn from(record: DownloadQueueRecord) -> Self {
+        Self {
+            paused: record.state == QueueState::Paused.as_str(),
+            file_id: record.file_id,
+            url: record.url,
+            save_path: PathBuf::from(record.save_path),
+            event_name: record.event_name,
+            priority: record.priority,
+            payload: record.payload,
+            validator: record.validator,
+            total_bytes: record.total_bytes,
+            downloaded_bytes: record.downloaded_bytes,
+            expected_sha256: None,
+            mirrors: Vec::new(),
+        }
+    }
+}
+
+/// 下载结果
+#[derive(Debug, Clone)]
+pub struct DownloadOutcome {
+    /// 文件的 SHA-256（小写十六进制）
+    pub sha256: String,
+    /// 实际完成下载的地址（原地址或备用地址）
+    pub url: String,
+}
+
+/// 已加入队列的下载，交给 `download_file_with_progress` 执行
+pub struct EnqueuedDownload {
+    task: DownloadTask,
+    control: Arc<DownloadControl>,
+    _guard: ControlGuard,
+}
+
+impl EnqueuedDownload {
+    pub fn save_path(&self) -> &PathBuf {
+        &self.task.save_path
+    }
+}
+
+/// 把下载加入队列，同一文件不能重复排队（失败的条目会被替换）
+pub fn enqueue(task: DownloadTask) -> AppResult<EnqueuedDownload> {
+    let state = if task.paused {
+        QueueState::Paused
+    } else {
+        QueueState::Queued
+    };
+
+    {
+        let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
+        if queue
+            .iter()
+            .any(|e| e.file_id == task.file_id && e.state != QueueState::Failed)
+        {
+            return Err(AppError::ValidationError(format!(
+                "已在下载队列中: {}",
+                task.file_id
+            )));
+        }
+        // 重新下载失败的条目时替换原记录
+        queue.retain(|e| e.file_id != task.file_id);
+        queue.push(QueueEntry {
+            file_id: task.file_id.clone(),
+            priority: task.priority,
+            state,
+        });
+    }
+
+    let control = Arc::new(DownloadControl {
+        state: AtomicU8::new(if task.paused {
+            STATE_PAUSED
+        } else {
+            STATE_RUNNING
+        }),
+        notify: Notify::new(),
+    });
+    DOWNLOAD_CONTROLS
+        .lock()
+        .unwrap()
+        .insert(task.file_id.clone(), control.clone());
+    let _guard = ControlGuard {
+        file_id: task.file_id.clone(),
+    };
+
+    Ok(EnqueuedDownload {
+        task,
+        control,
+        _guard,
+    })
 }
 
-// 通用文件下载函数
+/// 读取上次退出时未完成的下载，按队列顺序返回
+pub async fn saved_downloads() -> AppResult<Vec<DownloadTask>> {
+    let records = storage::get_download_records()
+        .await
+        .map_err(|e| AppError::ExecutionError(format!("读取下载队列失败: {}", e)))?;
+    Ok(records.into_iter().map(DownloadTask::from).collect())
+}
+
+/// 删除保存的下载记录
+pub async fn forget_download(file_id: &str) {
+    if let Err(e) = storage::remove_download_record(file_id.to_string()).await {
+        warn!("删除下载记录失败 {}: {}", file_id, e);
+    }
+}
+
+/// 通用文件下载函数
+///
+/// 等待队列分配槽位后开始下载，同时运行的数量受设置限制，优先级高的先开始。
+/// 下载可通过 `pause_download` / `resume_download` 暂停和继续，
+/// 通过 `cancel_download` 取消，取消时删除未完成的文件并返回 `CancelledError`。
+/// 队列和进度保存在数据库中，应用重启后可以恢复。
+/// 下载地址失败（连接错误或 HTTP 错误）时依次改用备用地址。
+/// 完成后返回文件的 SHA-256，任务带有期望值且不一致时返回 `ValidationError`。
 pub async fn download_file_with_progress<R: tauri::Runtime>(
     app_handle: tauri::AppHandle<R>,
-    url: &str,
+    download: EnqueuedDownload,
+) -> AppResult<DownloadOutcome> {
+    let task = &download.task;
+    let file_id = task.file_id.as_str();
+    let save_path = &task.save_path;
+
+    // 保存到数据库，应用退出后可以恢复
+    let queued = DOWNLOAD_QUEUE
+        .lock()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .find(|(_, e)| e.file_id == file_id)
+        .map(|(position, e)| (position, e.state));
+    if let Some((position, state)) = queued {
+        if let Err(e) = storage::save_download_record(task.record(position, state)).await {
+            warn!("保存下载记录失败 {}: {}", file_id, e);
+        }
+    }
+
+    let result = transfer(&app_handle, task, &download.control).await;
+    if let Err(e) = &result {
+        let downloaded_bytes = fs::metadata(save_path).map(|m| m.len()).unwrap_or(0);
+        let progress = match e {
+            AppError::CancelledError(_) => {
+                let _ = fs::remove_file(save_path);
+                remove_part_files(save_path);
+                ACTIVE_DOWNLOADS.lock().unwrap().remove(file_id);
+                progress_of(file_id, downloaded_bytes, None, "cancelled", None)
+            }
+            _ => {
+                // 失败的下载留在队列中，便于查看和重试
+                set_queue_state(file_id, QueueState::Failed);
+                let progress = progress_of(file_id, downloaded_bytes, None, "error", None);
+                ACTIVE_DOWNLOADS
+                    .lock()
+                    .unwrap()
+                    .insert(file_id.to_string(), progress.clone());
+                progress
+            }
+        };
+        let _ = app_handle.emit(&task.event_name, progress);
+    }
+
+    // 下载已结束，重启后无需恢复
+    forget_download(file_id).await;
+    result
+}
+
+// 保存下载进度，失败只记录日志
+async fn persist_progress(
     file_id: &str,
-    save_path: &PathBuf,
-    event_name: &str,
-) -> AppResult<()> {
-    // 确保父目录存在
-    if let Some(parent) = save_path.parent() {
-        fs::create_dir_all(parent)?;
+    downloaded: u64,
+    total_size: Option<u64>,
+    validator: &Option<String>,
+) {
+    if let Err(e) =
+        storage::update_download_progress(file_id.to_string(), downloaded, total_size, validator.clone())
+            .await
+    {
+        warn!("保存下载进度失败 {}: {}", file_id, e);
     }
+}
+
+// 在后台保存队列顺序、优先级和状态
+//
+// 快照在写锁内获取，后执行的写入总是保存更新的队列，不会被先发起的写入覆盖
+fn persist_queue() {
+    tauri::async_runtime::spawn(async {
+        let _write = QUEUE_WRITE.lock().await;
+        let entries: Vec<(String, i32, String)> = DOWNLOAD_QUEUE
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| (e.file_id.clone(), e.priority, e.state.as_str().to_string()))
+            .collect();
+        if let Err(e) = storage::save_download_queue_order(entries).await {
+            warn!("保存下载队列失败: {}", e);
+        }
+    });
+}
+
+// 按模板改写下载地址，模板中 `{url}` 为原地址，`{encoded_url}` 为编码后的原地址，
+// `{path}` 为路径和查询参数，`{filename}` 为文件名
+fn rewrite_url(template: &str, url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let path = match parsed.query() {
+        Some(query) => format!("{}?{}", parsed.path(), query),
+        None => parsed.path().to_string(),
+    };
+    let filename = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default();
+    let encoded_url: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
 
-    // 准备进度跟踪
-    let file_id = file_id.to_string();
-    let mut last_notify_time = Instant::now();
+    let rewritten = template
+        .replace("{url}", url)
+        .replace("{encoded_url}", &encoded_url)
+        .replace("{path}", &path)
+        .replace("{filename}", filename);
+    (rewritten != url).then_some(rewritten)
+}
 
-    // 下载文件
-    let client = reqwest::Client::new();
-    let res = client.get(url).send().await?;
+// 候选下载地址：原地址、任务附带的备用地址、按设置中的模板改写的地址，去掉重复项
+fn candidate_urls(task: &DownloadTask) -> Vec<String> {
+    let templates = QUEUE_SETTINGS.read().unwrap().mirror_templates.clone();
+    let rewritten = templates
+        .iter()
+        .filter_map(|template| rewrite_url(template.trim(), &task.url));
 
-    if !res.status().is_success() {
-        return Err(AppError::DownloadError(format!(
-            "下载失败，HTTP状态码: {}",
-            res.status()
-        )));
+    let mut candidates = vec![task.url.clone()];
+    for url in task.mirrors.iter().cloned().chain(rewritten) {
+        if !url.is_empty() && !candidates.contains(&url) {
+            candidates.push(url);
+        }
     }
+    candidates
+}
 
-    // 获取文件总大小
-    let total_size = res.content_length();
+// 当前地址失败时换用下一个候选地址，没有更多地址时返回 false
+fn switch_mirror(file_id: &str, candidates: &[String], mirror: &mut usize, error: &AppError) -> bool {
+    if *mirror + 1 >= candidates.len() {
+        return false;
+    }
+    *mirror += 1;
+    warn!(
+        "下载地址失败，改用备用地址 {}: {} ({})",
+        candidates[*mirror], file_id, error
+    );
+    true
+}
 
-    // 发送初始进度
-    let progress = DownloadProgress {
-        trainer_id: file_id.clone(), // 在这里用作文件ID
-        progress: 0.0,
-        downloaded_bytes: 0,
-        total_bytes: total_size,
-        status: "downloading".to_string(),
-        speed: None,
+fn segment_count() -> u32 {
+    QUEUE_SETTINGS.read().unwrap().segments
+}
+
+// 把文件分成不超过 `count` 段，返回各段的起止位置（包含两端），每段不小于 `MIN_SEGMENT_SIZE`
+fn plan_segments(total: u64, count: u32) -> Vec<(u64, u64)> {
+    let count = u64::from(count).min(total / MIN_SEGMENT_SIZE).max(1);
+    let size = total.div_ceil(count);
+    (0..count)
+        .map(|i| i * size)
+        .take_while(|&start| start < total)
+        .map(|start| (start, (start + size).min(total) - 1))
+        .collect()
+}
+
+// 计算文件前 `len` 字节的 SHA-256，续传时补上之前已下载的部分
+fn hash_prefix(path: &Path, len: u64) -> io::Result<Sha256> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?.take(len), &mut hasher)?;
+    Ok(hasher)
+}
+
+// 分段的部分文件，与目标文件放在同一目录
+fn part_path(save_path: &Path, start: u64, end: u64) -> PathBuf {
+    let mut name = save_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}-{}.part", start, end));
+    save_path.with_file_name(name)
+}
+
+// 删除目标文件的所有部分文件
+fn remove_part_files(save_path: &Path) {
+    let (Some(parent), Some(name)) = (save_path.parent(), save_path.file_name()) else {
+        return;
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(parent) else {
+        return;
     };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with(&prefix) && file_name.ends_with(".part") {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
 
-    // 保存到活动下载列表
-    {
-        let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-        downloads.insert(file_id.clone(), progress.clone());
+// 分段下载的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentOutcome {
+    Finished,
+    // 暂停或取消
+    Interrupted,
+    // 服务器上的文件已变化，需要重新下载
+    Changed,
+}
+
+// 使分段提前结束的情况，作为错误交给 try_join_all 以便立即停止其他分段
+enum SegmentStop {
+    Changed,
+    Failed(AppError),
+}
+
+// 多连接分段下载，各段写入单独的部分文件，全部完成后按顺序合并
+struct SegmentedDownload<'a, R: tauri::Runtime> {
+    app_handle: &'a tauri::AppHandle<R>,
+    task: &'a DownloadTask,
+    // 当前使用的下载地址
+    url: &'a str,
+    control: &'a DownloadControl,
+    // 各连接共用该下载的限速令牌桶
+    bucket: &'a Mutex<TokenBucket>,
+    ranges: &'a [(u64, u64)],
+    total: u64,
+    validator: &'a str,
+}
+
+impl<R: tauri::Runtime> SegmentedDownload<'_, R> {
+    // 各部分文件中已下载的字节数
+    fn received(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| {
+                let len = fs::metadata(part_path(&self.task.save_path, start, end))
+                    .map(|m| m.len())
+                    .unwrap_or(0);
+                len.min(end - start + 1)
+            })
+            .sum()
     }
 
-    // 发送进度事件
-    let _ = app_handle.emit(event_name, progress);
+    async fn run(&self) -> AppResult<SegmentOutcome> {
+        let file_id = self.task.file_id.as_str();
+        let received = AtomicU64::new(self.received());
+        let received_ref = &received;
+        // 一个分段发现文件变化时，其他分段的数据也已失效，不再继续下载
+        let segments = try_join_all(self.ranges.iter().map(|&(start, end)| async move {
+            match self.download_segment(start, end, received_ref).await {
+                Ok(SegmentOutcome::Changed) => Err(SegmentStop::Changed),
+                Ok(outcome) => Ok(outcome),
+                Err(e) => Err(SegmentStop::Failed(e)),
+            }
+        }));
+        tokio::pin!(segments);
 
-    // 准备写入文件
-    let mut file = fs::File::create(save_path)?;
-    let mut downloaded: u64 = 0;
-    let mut stream = res.bytes_stream();
-    let mut last_downloaded = 0u64;
-    let mut last_speed_check = Instant::now();
+        // 汇总各段的进度，定时发送
+        let mut ticker = tokio::time::interval(Duration::from_millis(200));
+        let mut last_received = received.load(Ordering::SeqCst);
+        let mut last_speed_check = Instant::now();
+        let mut speed = None;
+        let outcomes = loop {
+            tokio::select! {
+                outcomes = &mut segments => match outcomes {
+                    Ok(outcomes) => break outcomes,
+                    Err(SegmentStop::Changed) => return Ok(SegmentOutcome::Changed),
+                    Err(SegmentStop::Failed(e)) => return Err(e),
+                },
+                _ = ticker.tick() => {
+                    let downloaded = received.load(Ordering::SeqCst);
+                    let elapsed = last_speed_check.elapsed();
+                    if elapsed >= Duration::from_secs(1) {
+                        let speed_kbps = ((downloaded - last_received) as f64 / elapsed.as_secs_f64()) / 1024.0;
+                        speed = Some(speed_kbps);
+                        last_speed_check = Instant::now();
+                        last_received = downloaded;
+                        persist_progress(file_id, downloaded, Some(self.total), &Some(self.validator.to_string())).await;
+                    }
+                    report(
+                        self.app_handle,
+                        &self.task.event_name,
+                        progress_of(file_id, downloaded, Some(self.total), "downloading", speed),
+                    );
+                }
+            }
+        };
 
-    // 处理流式下载
-    while let Some(item) = stream.next().await {
-        let chunk = item?;
-        file.write_all(&chunk)?;
+        if outcomes.contains(&SegmentOutcome::Interrupted) {
+            return Ok(SegmentOutcome::Interrupted);
+        }
+        Ok(SegmentOutcome::Finished)
+    }
 
-        downloaded += chunk.len() as u64;
+    // 按顺序合并各段，合并时计算 SHA-256
+    fn merge(&self) -> AppResult<Sha256> {
+        let mut output = fs::File::create(&self.task.save_path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        for &(start, end) in self.ranges {
+            let mut part = fs::File::open(part_path(&self.task.save_path, start, end))?;
+            loop {
+                let len = part.read(&mut buffer)?;
+                if len == 0 {
+                    break;
+                }
+                output.write_all(&buffer[..len])?;
+                hasher.update(&buffer[..len]);
+            }
+        }
+        output.flush()?;
+        remove_part_files(&self.task.save_path);
+        info!("分段下载完成并已合并: {}", self.task.file_id);
+        Ok(hasher)
+    }
 
-        // 计算进度百分比
-        let percent = if let Some(total) = total_size {
-            (downloaded as f64 / total as f64) * 100.0
-        } else {
-            0.0 // 无法确定总大小
+    // 下载一个分段，失败时只重试该分段
+    async fn download_segment(&self, start: u64, end: u64, received: &AtomicU64) -> AppResult<SegmentOutcome> {
+        let mut attempt = 0;
+        loop {
+            match self.fetch_segment(start, end, received).await {
+                Err(e) if attempt < SEGMENT_MAX_RETRIES && self.control.state() == STATE_RUNNING => {
+                    let delay = SEGMENT_RETRY_DELAY * 2u32.pow(attempt);
+                    warn!(
+                        "分段 {}-{} 下载失败，{} 秒后重试: {}",
+                        start,
+                        end,
+                        delay.as_secs(),
+                        e
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = self.control.wait_while(STATE_RUNNING) => return Ok(SegmentOutcome::Interrupted),
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // 从部分文件的末尾继续下载分段
+    async fn fetch_segment(&self, start: u64, end: u64, received: &AtomicU64) -> AppResult<SegmentOutcome> {
+        let path = part_path(&self.task.save_path, start, end);
+        let size = end - start + 1;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut offset = file.metadata()?.len();
+        if offset > size {
+            file.set_len(size)?;
+            offset = size;
+        }
+        if offset == size {
+            return Ok(SegmentOutcome::Finished);
+        }
+
+        let request = http::send(|client| {
+            client
+                .get(self.url)
+                .header(RANGE, format!("bytes={}-{}", start + offset, end))
+                .header(IF_RANGE, self.validator)
+        });
+        let res = tokio::select! {
+            res = request => res?,
+            _ = self.control.wait_while(STATE_RUNNING) => return Ok(SegmentOutcome::Interrupted),
         };
+        match res.status() {
+            StatusCode::PARTIAL_CONTENT => {}
+            // If-Range 不匹配时服务器返回完整文件
+            status if status.is_success() => return Ok(SegmentOutcome::Changed),
+            status => {
+                return Err(AppError::DownloadError(format!(
+                    "分段下载失败，HTTP状态码: {}",
+                    status
+                )));
+            }
+        }
 
-        // 计算下载速度（每秒更新一次）
-        let mut current_speed = None;
-        if last_speed_check.elapsed() >= Duration::from_secs(1) {
-            let elapsed = last_speed_check.elapsed().as_secs_f64();
-            if elapsed > 0.0 {
-                let bytes_since_last = downloaded - last_downloaded;
-                let speed_kbps = (bytes_since_last as f64 / elapsed) / 1024.0; // KB/s
-                current_speed = Some(speed_kbps);
-
-                last_speed_check = Instant::now();
-                last_downloaded = downloaded;
-            }
-        }
-
-        // 限制进度通知频率，避免过多的事件
-        if last_notify_time.elapsed() >= Duration::from_millis(200) {
-            let progress = DownloadProgress {
-                trainer_id: file_id.clone(),
-                progress: percent,
-                downloaded_bytes: downloaded,
-                total_bytes: total_size,
-                status: "downloading".to_string(),
-                speed: current_speed,
+        let mut stream = res.bytes_stream();
+        while offset < size {
+            let item = tokio::select! {
+                item = stream.next() => item,
+                _ = self.control.wait_while(STATE_RUNNING) => return Ok(SegmentOutcome::Interrupted),
             };
+            let Some(item) = item else {
+                break;
+            };
+            let chunk = item?;
+            // 忽略超出分段范围的数据
+            let len = (chunk.len() as u64).min(size - offset);
+            file.write_all(&chunk[..len as usize])?;
+            offset += len;
+            received.fetch_add(len, Ordering::SeqCst);
 
-            // 更新活动下载列表
-            {
-                let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-                downloads.insert(file_id.clone(), progress.clone());
+            let wait = bandwidth::reserve(&mut self.bucket.lock().unwrap(), len);
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = self.control.wait_while(STATE_RUNNING) => return Ok(SegmentOutcome::Interrupted),
             }
+        }
+        file.flush()?;
 
-            // 发送进度事件
-            let _ = app_handle.emit(event_name, progress);
-            last_notify_time = Instant::now();
+        if offset < size {
+            return Err(AppError::DownloadError(format!(
+                "分段 {}-{} 数据不完整: {}/{} 字节",
+                start, end, offset, size
+            )));
         }
+        Ok(SegmentOutcome::Finished)
     }
+}
 
-    file.flush()?;
+async fn transfer<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    task: &DownloadTask,
+    control: &DownloadControl,
+) -> AppResult<DownloadOutcome> {
+    let candidates = candidate_urls(task);
+    let mut mirror = 0;
+    let file_id = task.file_id.as_str();
+    let save_path = &task.save_path;
+    let event_name = task.event_name.as_str();
 
-    // 发送完成进度
-    let final_progress = DownloadProgress {
-        trainer_id: file_id.clone(),
-        progress: 100.0,
-        downloaded_bytes: downloaded,
-        total_bytes: total_size,
-        status: "completed".to_string(),
-        speed: None,
+    // 确保父目录存在
+    if let Some(parent) = save_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // 恢复的任务从上次保存的进度继续；进度之后的数据可能没有写完，续传时截掉，
+    // 部分文件比记录短时以文件长度为准
+    let mut validator = task.validator.clone();
+    let mut total_size = task.total_bytes;
+    let mut downloaded: u64 = match &validator {
+        Some(_) => {
+            let file_len = fs::metadata(save_path).map(|m| m.len()).unwrap_or(0);
+            task.downloaded_bytes.min(file_len)
+        }
+        None => 0,
     };
+    // 分段下载的区间，确定分段后暂停再继续时直接续传各分段
+    let mut segments: Option<Vec<(u64, u64)>> = None;
+    let bucket = Mutex::new(TokenBucket::new(0));
+    // 已写入数据的哈希，与 `downloaded` 同步；为空时从已有的部分文件重新计算
+    let mut hasher: Option<Sha256> = None;
+
+    // 每次循环建立一次连接，暂停后重新连接并从断点继续
+    loop {
+        let url = candidates[mirror].as_str();
+        if control.state() == STATE_PAUSED {
+            report(
+                app_handle,
+                event_name,
+                progress_of(file_id, downloaded, total_size, "paused", None),
+            );
+            control.wait_while(STATE_PAUSED).await;
+        }
+        if control.state() == STATE_CANCELLED {
+            return Err(cancelled_error(file_id));
+        }
+
+        // 等待空闲的下载槽位
+        if !try_start(file_id) {
+            report(
+                app_handle,
+                event_name,
+                progress_of(file_id, downloaded, total_size, "queued", None),
+            );
+            if !acquire_slot(file_id, control).await {
+                continue;
+            }
+        }
+
+        if let (Some(ranges), Some(total), Some(tag)) = (&segments, total_size, &validator) {
+            let segmented = SegmentedDownload {
+                app_handle,
+                task,
+                url,
+                control,
+                bucket: &bucket,
+                ranges,
+                total,
+                validator: tag,
+            };
+            let outcome = match segmented.run().await {
+                Ok(outcome) => outcome,
+                Err(e) if switch_mirror(file_id, &candidates, &mut mirror, &e) => {
+                    // 新地址重新确认文件信息，校验值一致时保留已下载的分段
+                    segments = None;
+                    downloaded = 0;
+                    hasher = None;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match outcome {
+                SegmentOutcome::Finished => {
+                    hasher = Some(segmented.merge()?);
+                    downloaded = total;
+                    break;
+                }
+                SegmentOutcome::Interrupted => downloaded = segmented.received(),
+                SegmentOutcome::Changed => {
+                    warn!("文件已变化，重新下载: {}", file_id);
+                    remove_part_files(save_path);
+                    segments = None;
+                    validator = None;
+                    downloaded = 0;
+                    hasher = None;
+                }
+            }
+            continue;
+        }
+
+        // 已有部分数据且有校验值时请求剩余部分，文件变化时服务器会返回完整内容
+        let resume_from = validator
+            .clone()
+            .filter(|_| downloaded > 0)
+            .map(|validator| (downloaded, validator));
+        let request = http::send(|client| {
+            let request = client.get(url);
+            match &resume_from {
+                Some((offset, validator)) => request
+                    .header(RANGE, format!("bytes={}-", offset))
+                    .header(IF_RANGE, validator.as_str()),
+                None => request,
+            }
+        });
+        let res = tokio::select! {
+            res = request => res,
+            _ = control.wait_while(STATE_RUNNING) => continue,
+        };
+        let res = match res {
+            Ok(res) => res,
+            Err(e) if switch_mirror(file_id, &candidates, &mut mirror, &e) => continue,
+            Err(e) => return Err(e),
+        };
+
+        let mut file = match res.status() {
+            StatusCode::PARTIAL_CONTENT if resume_from.is_some() => {
+                info!("从 {} 字节处继续下载: {}", downloaded, file_id);
+                total_size = content_range_total(&res).or(total_size);
+                let file = OpenOptions::new().append(true).open(save_path)?;
+                file.set_len(downloaded)?;
+                file
+            }
+            StatusCode::RANGE_NOT_SATISFIABLE if resume_from.is_some() => {
+                warn!("断点续传请求无效，重新下载: {}", file_id);
+                downloaded = 0;
+                validator = None;
+                hasher = None;
+                continue;
+            }
+            status if status.is_success() => {
+                if resume_from.is_some() {
+                    warn!("服务器不支持断点续传或文件已变化，重新下载: {}", file_id);
+                }
+                let previous = validator.take();
+                downloaded = 0;
+                hasher = None;
+                total_size = res.content_length();
+                validator = resume_validator(&res);
+
+                // 服务器支持分段请求且文件足够大时改为多连接下载
+                let accepts_ranges = res
+                    .headers()
+                    .get(ACCEPT_RANGES)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value.eq_ignore_ascii_case("bytes"));
+                let ranges = match (total_size, &validator) {
+                    (Some(total), Some(_)) if accepts_ranges => plan_segments(total, segment_count()),
+                    _ => Vec::new(),
+                };
+                // 文件未变化时保留上次的分段文件继续下载
+                if ranges.len() <= 1 || previous.is_none() || previous != validator {
+                    remove_part_files(save_path);
+                }
+                if ranges.len() > 1 {
+                    info!("分 {} 段下载: {}", ranges.len(), file_id);
+                    persist_progress(file_id, downloaded, total_size, &validator).await;
+                    segments = Some(ranges);
+                    continue;
+                }
+                fs::File::create(save_path)?
+            }
+            status => {
+                let error = AppError::DownloadError(format!("下载失败，HTTP状态码: {}", status));
+                if switch_mirror(file_id, &candidates, &mut mirror, &error) {
+                    continue;
+                }
+                return Err(error);
+            }
+        };
+        persist_progress(file_id, downloaded, total_size, &validator).await;
+        if hasher.is_none() {
+            hasher = Some(hash_prefix(save_path, downloaded)?);
+        }
+
+        // 发送初始进度
+        report(
+            app_handle,
+            event_name,
+            progress_of(file_id, downloaded, total_size, "downloading", None),
+        );
+
+        let mut stream = res.bytes_stream();
+        let mut last_notify_time = Instant::now();
+        let mut last_downloaded = downloaded;
+        let mut last_speed_check = Instant::now();
+        // 暂停或取消时断开连接，回到外层循环处理
+        let mut interrupted = false;
+        // 连接中断时记录错误，换用下一个地址续传
+        let mut stream_error = None;
+
+        // 处理流式下载，等待数据时同时响应暂停和取消
+        loop {
+            let item = tokio::select! {
+                item = stream.next() => item,
+                _ = control.wait_while(STATE_RUNNING) => {
+                    interrupted = true;
+                    break;
+                }
+            };
+            let Some(item) = item else {
+                break;
+            };
+            let chunk = match item {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    stream_error = Some(AppError::from(e));
+                    break;
+                }
+            };
+            file.write_all(&chunk)?;
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&chunk);
+            }
+
+            downloaded += chunk.len() as u64;
+
+            // 按限速设置等待，等待期间同样响应暂停和取消
+            let wait = bandwidth::reserve(&mut bucket.lock().unwrap(), chunk.len() as u64);
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = control.wait_while(STATE_RUNNING) => {
+                    interrupted = true;
+                    break;
+                }
+            }
+
+            // 计算下载速度（每秒更新一次）
+            let mut current_speed = None;
+            if last_speed_check.elapsed() >= Duration::from_secs(1) {
+                let elapsed = last_speed_check.elapsed().as_secs_f64();
+                if elapsed > 0.0 {
+                    let bytes_since_last = downloaded - last_downloaded;
+                    let speed_kbps = (bytes_since_last as f64 / elapsed) / 1024.0; // KB/s
+                    current_speed = Some(speed_kbps);
+
+                    last_speed_check = Instant::now();
+                    last_downloaded = downloaded;
+                }
+                persist_progress(file_id, downloaded, total_size, &validator).await;
+            }
+
+            // 限制进度通知频率，避免过多的事件
+            if last_notify_time.elapsed() >= Duration::from_millis(200) {
+                report(
+                    app_handle,
+                    event_name,
+                    progress_of(file_id, downloaded, total_size, "downloading", current_speed),
+                );
+                last_notify_time = Instant::now();
+            }
+        }
+
+        file.flush()?;
+        if let Some(error) = stream_error {
+            persist_progress(file_id, downloaded, total_size, &validator).await;
+            if switch_mirror(file_id, &candidates, &mut mirror, &error) {
+                continue;
+            }
+            return Err(error);
+        }
+        if !interrupted {
+            break;
+        }
+        persist_progress(file_id, downloaded, total_size, &validator).await;
+    }
+
+    // 校验文件内容，不一致时删除文件
+    let hasher = match hasher {
+        Some(hasher) => hasher,
+        None => hash_prefix(save_path, downloaded)?,
+    };
+    let sha256 = format!("{:x}", hasher.finalize());
+    if let Some(expected) = &task.expected_sha256 {
+        if !expected.trim().eq_ignore_ascii_case(&sha256) {
+            let _ = fs::remove_file(save_path);
+            return Err(AppError::ValidationError(format!(
+                "文件校验失败，SHA-256 不一致: 期望 {}，实际 {}",
+                expected.trim(),
+                sha256
+            )));
+        }
+        info!("SHA-256 校验通过: {}", file_id);
+    }
+
+    let url = candidates[mirror].clone();
+    if mirror > 0 {
+        info!("已通过备用地址完成下载: {} ({})", url, file_id);
+    }
+
+    // 发送完成进度，附带实际使用的下载地址
+    let mut final_progress = progress_of(file_id, downloaded, total_size, "completed", None);
+    final_progress.progress = 100.0;
+    final_progress.url = Some(url.clone());
     let _ = app_handle.emit(event_name, final_progress);
 
     // 从活动下载列表移除
     {
         let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-        downloads.remove(&file_id);
+        downloads.remove(file_id);
     }
 
-    Ok(())
+    Ok(DownloadOutcome { sha256, url })
+}
+
+// 暂停下载，保留已下载的部分
+#[tauri::command]
+pub fn pause_download(file_id: String) -> AppResult<bool> {
+    let control = DOWNLOAD_CONTROLS.lock().unwrap().get(&file_id).cloned();
+    let paused = control.is_some_and(|control| control.transition(STATE_RUNNING, STATE_PAUSED));
+    if paused {
+        // 暂停的下载让出槽位
+        set_queue_state(&file_id, QueueState::Paused);
+    }
+    Ok(paused)
+}
+
+// 继续已暂停的下载，服务器支持时从断点续传
+#[tauri::command]
+pub fn resume_download(file_id: String) -> AppResult<bool> {
+    let control = DOWNLOAD_CONTROLS.lock().unwrap().get(&file_id).cloned();
+    let resumed = control.is_some_and(|control| control.transition(STATE_PAUSED, STATE_RUNNING));
+    if resumed {
+        // 回到队列，由队列决定何时重新开始
+        set_queue_state(&file_id, QueueState::Queued);
+    }
+    Ok(resumed)
+}
+
+/// 继续所有已暂停的下载，返回继续的数量
+pub fn resume_all_downloads() -> usize {
+    let paused: Vec<String> = DOWNLOAD_QUEUE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.state == QueueState::Paused)
+        .map(|e| e.file_id.clone())
+        .collect();
+    paused
+        .into_iter()
+        .filter(|file_id| resume_download(file_id.clone()).unwrap_or(false))
+        .count()
+}
+
+// 修改排队中下载的优先级
+#[tauri::command]
+pub fn set_download_priority(file_id: String, priority: i32) -> AppResult<bool> {
+    let updated = match DOWNLOAD_QUEUE
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .find(|e| e.file_id == file_id)
+    {
+        Some(entry) => {
+            entry.priority = priority;
+            true
+        }
+        None => false,
+    };
+    QUEUE_CHANGED.notify_waiters();
+    persist_queue();
+    Ok(updated)
+}
+
+// 调整下载在队列中的位置，同优先级时靠前的先开始
+#[tauri::command]
+pub fn move_download(file_id: String, position: usize) -> AppResult<bool> {
+    let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
+    let Some(index) = queue.iter().position(|e| e.file_id == file_id) else {
+        return Ok(false);
+    };
+    let entry = queue.remove(index);
+    let position = position.min(queue.len());
+    queue.insert(position, entry);
+    drop(queue);
+    QUEUE_CHANGED.notify_waiters();
+    persist_queue();
+    Ok(true)
 }
 
-// 取消下载
+// 取消下载，正在进行的下载会中止并清理未完成的文件
 #[tauri::command]
 pub fn cancel_download(file_id: String) -> AppResult<bool> {
-    let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-    if downloads.contains_key(&file_id) {
-        downloads.remove(&file_id);
-        Ok(true)
-    } else {
-        Ok(false)
+    let control = DOWNLOAD_CONTROLS.lock().unwrap().get(&file_id).cloned();
+    match control {
+        Some(control) => {
+            control.cancel();
+            Ok(true)
+        }
+        // 已失败的条目直接移出队列
+        None => {
+            DOWNLOAD_QUEUE.lock().unwrap().retain(|e| e.file_id != file_id);
+            Ok(ACTIVE_DOWNLOADS.lock().unwrap().remove(&file_id).is_some())
+        }
     }
 }
 
 // 清理所有下载
 #[tauri::command]
 pub fn clear_all_downloads() -> AppResult<usize> {
+    let controls = DOWNLOAD_CONTROLS.lock().unwrap();
+    for control in controls.values() {
+        control.cancel();
+    }
+
+    // 正在进行的下载取消后自行移出队列，这里只移除失败的条目
+    let mut queue = DOWNLOAD_QUEUE.lock().unwrap();
+    let count = queue.len();
     let mut downloads = ACTIVE_DOWNLOADS.lock().unwrap();
-    let count = downloads.len();
-    downloads.clear();
+    for entry in queue.iter().filter(|e| e.state == QueueState::Failed) {
+        downloads.remove(&entry.file_id);
+    }
+    queue.retain(|e| e.state != QueueState::Failed);
     Ok(count)
 }
 
@@ -250,3 +1396,69 @@ fn windows_str(s: &str) -> *mut u16 {
         .collect();
     wide.as_mut_ptr()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file_id: &str, priority: i32, state: QueueState) -> QueueEntry {
+        QueueEntry {
+            file_id: file_id.to_string(),
+            priority,
+            state,
+        }
+    }
+
+    #[test]
+    fn rewrite_url_fills_template_placeholders() {
+        let url = "https://example.com/files/trainer.zip?v=2";
+        assert_eq!(
+            rewrite_url("https://mirror.example.org{path}", url).as_deref(),
+            Some("https://mirror.example.org/files/trainer.zip?v=2")
+        );
+        assert_eq!(
+            rewrite_url("https://proxy.example.org/{url}", url).as_deref(),
+            Some("https://proxy.example.org/https://example.com/files/trainer.zip?v=2")
+        );
+        assert_eq!(
+            rewrite_url("https://cdn.example.org/get?name={filename}&src={encoded_url}", url).as_deref(),
+            Some("https://cdn.example.org/get?name=trainer.zip&src=https%3A%2F%2Fexample.com%2Ffiles%2Ftrainer.zip%3Fv%3D2")
+        );
+        assert_eq!(rewrite_url("{url}", url), None);
+        assert_eq!(rewrite_url("https://mirror.example.org{path}", "not a url"), None);
+    }
+
+    #[test]
+    fn plan_segments_splits_large_files_only() {
+        assert_eq!(plan_segments(100, 4), vec![(0, 99)]);
+        assert!(plan_segments(0, 4).is_empty());
+
+        let total = 10 * MIN_SEGMENT_SIZE + 1;
+        let ranges = plan_segments(total, 4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[3].1, total - 1);
+        assert!(ranges.windows(2).all(|w| w[0].1 + 1 == w[1].0));
+
+        // 分段数受最小分段大小限制
+        assert_eq!(plan_segments(2 * MIN_SEGMENT_SIZE, 8).len(), 2);
+    }
+
+    #[test]
+    fn next_to_start_respects_limit_priority_and_order() {
+        let mut queue = vec![
+            entry("a", 0, QueueState::Running),
+            entry("b", 0, QueueState::Queued),
+            entry("c", 5, QueueState::Paused),
+            entry("d", 1, QueueState::Queued),
+            entry("e", 1, QueueState::Queued),
+        ];
+
+        assert_eq!(next_to_start(&queue, 1), None);
+        // 优先级高的先开始，同优先级按队列顺序，暂停的不参与
+        assert_eq!(next_to_start(&queue, 2), Some(3));
+
+        queue[3].state = QueueState::Running;
+        assert_eq!(next_to_start(&queue, 3), Some(4));
+    }
+}
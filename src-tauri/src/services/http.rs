@@ -0,0 +1,193 @@
+use crate::api::error::{AppError, AppResult};
+use crate::services::offline;
+use crate::services::settings::{self, ProxyMode, ProxySettings};
+use lazy_static::lazy_static;
+use log::{info, warn};
+use reqwest::{Client, NoProxy, Proxy, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+// 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// 两次读取之间的最长等待时间，大文件下载不受总时长限制
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+// 失败后的最大重试次数
+const MAX_RETRIES: u32 = 3;
+// 首次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 所有请求统一使用的 User-Agent
+pub const USER_AGENT: &str = concat!("GameModMaster/", env!("CARGO_PKG_VERSION"));
+
+lazy_static! {
+    static ref CLIENT: RwLock<Client> = RwLock::new(initial_client());
+}
+
+// 按保存的代理设置创建客户端，设置无效时退回系统代理
+fn initial_client() -> Client {
+    let proxy = settings::load_settings()
+        .map(|s| s.proxy)
+        .unwrap_or_default();
+    build_client(&proxy)
+        .or_else(|e| {
+            warn!("代理设置无效，使用系统代理: {}", e);
+            build_client(&ProxySettings::default())
+        })
+        .expect("无法创建 HTTP 客户端")
+}
+
+// 手动代理，SOCKS5 使用 socks5h 以便由代理服务器解析域名
+fn manual_proxy(proxy: &ProxySettings) -> AppResult<Proxy> {
+    let scheme = match proxy.mode {
+        ProxyMode::Socks5 => "socks5h",
+        _ => "http",
+    };
+    let mut manual = Proxy::all(format!("{}://{}:{}", scheme, proxy.host.trim(), proxy.port))
+        .map_err(|e| AppError::ConfigError(format!("代理地址无效: {}", e)))?;
+
+    if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+        manual = manual.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+    }
+    if !proxy.bypass.is_empty() {
+        manual = manual.no_proxy(NoProxy::from_string(&proxy.bypass.join(",")));
+    }
+    Ok(manual)
+}
+
+/// 按代理设置创建客户端
+pub fn build_client(proxy: &ProxySettings) -> AppResult<Client> {
+    let builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT);
+
+    let builder = match proxy.mode {
+        ProxyMode::None => builder.no_proxy(),
+        // reqwest 默认读取系统代理
+        ProxyMode::System => builder,
+        ProxyMode::Http | ProxyMode::Socks5 => builder.proxy(manual_proxy(proxy)?),
+    };
+    Ok(builder.build()?)
+}
+
+/// 设置变更后重建共享客户端
+pub fn apply_proxy(proxy: &ProxySettings) -> AppResult<()> {
+    let client = build_client(proxy)?;
+    *CLIENT.write().unwrap() = client;
+    info!("已应用代理设置: {:?}", proxy.mode);
+    Ok(())
+}
+
+/// 获取共享的 HTTP 客户端（内部共享连接池，克隆开销很小）
+pub fn client() -> Client {
+    CLIENT.read().unwrap().clone()
+}
+
+/// 代理测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyTestResult {
+    pub success: bool,
+    /// HTTP 状态码，连接失败时为空
+    pub status: Option<u16>,
+    /// 请求耗时（毫秒）
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 使用指定代理设置请求测试地址（不重试）
+pub async fn test_proxy(proxy: &ProxySettings, url: &str) -> AppResult<ProxyTestResult> {
+    let client = build_client(proxy)?;
+    let started = Instant::now();
+    let result = client.get(url).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => ProxyTestResult {
+            success: response.status().is_success(),
+            status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ProxyTestResult {
+            success: false,
+            status: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+// 连接失败、超时、服务端错误和限流可以重试，其余错误（例如代理配置无效）直接返回
+fn should_retry(result: &reqwest::Result<Response>) -> bool {
+    match result {
+        Ok(response) => {
+            response.status().is_server_error()
+                || response.status() == StatusCode::TOO_MANY_REQUESTS
+        }
+        Err(e) => e.is_connect() || e.is_timeout(),
+    }
+}
+
+/// 发送请求，失败时按指数退避重试
+///
+/// `build` 每次重试都会被调用以重新构造请求。重试用尽后返回最后一次的响应，
+/// 状态码由调用方检查。
+pub async fn send<F>(build: F) -> AppResult<Response>
+where
+    F: Fn(&Client) -> RequestBuilder,
+{
+    if offline::is_forced() {
+        return Err(AppError::OfflineError("已禁止网络请求".to_string()));
+    }
+
+    let client = client();
+    let mut attempt = 0;
+
+    loop {
+        let result = build(&client).send().await;
+        if attempt >= MAX_RETRIES || !should_retry(&result) {
+            return Ok(result?);
+        }
+
+        let delay = RETRY_BASE_DELAY * 2u32.pow(attempt);
+        match &result {
+            Ok(response) => warn!(
+                "请求返回 {}，{} 毫秒后重试: {}",
+                response.status(),
+                delay.as_millis(),
+                response.url()
+            ),
+            Err(e) => warn!("请求失败，{} 毫秒后重试: {}", delay.as_millis(), e),
+        }
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// GET 请求（带重试）
+pub async fn get(url: &str) -> AppResult<Response> {
+    send(|client| client.get(url)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16) -> reqwest::Result<Response> {
+        Ok(Response::from(
+            tauri::http::Response::builder()
+                .status(status)
+                .body(Vec::new())
+                .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn retries_server_errors_and_rate_limits_only() {
+        assert!(should_retry(&response(503)));
+        assert!(should_retry(&response(429)));
+        assert!(!should_retry(&response(404)));
+        assert!(!should_retry(&response(200)));
+    }
+}
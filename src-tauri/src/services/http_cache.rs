@@ -0,0 +1,166 @@
+use crate::api::error::{AppError, AppResult};
+use crate::services::http;
+use crate::services::settings;
+use crate::services::storage::{self, HttpCacheEntry};
+use crate::utils::path::get_app_dir;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::warn;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+// 页面缓存目录名
+const CACHE_DIR_NAME: &str = "http_cache";
+// 超过该时间的缓存不再直接返回，需要先向服务器确认
+const STALE_MAX_AGE: i64 = 1000 * 60 * 60 * 24; // 1天
+
+// 临时文件序号，同一地址的并发写入各用各的临时文件
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    // 是否开启 stale-while-revalidate，保存设置时更新
+    static ref STALE_WHILE_REVALIDATE: AtomicBool = AtomicBool::new(
+        settings::load_settings()
+            .map(|s| s.stale_while_revalidate)
+            .unwrap_or(false)
+    );
+}
+
+/// 设置变更后更新 stale-while-revalidate 开关
+pub fn apply(stale_while_revalidate: bool) {
+    STALE_WHILE_REVALIDATE.store(stale_while_revalidate, Ordering::Relaxed);
+}
+
+fn cache_dir() -> AppResult<PathBuf> {
+    Ok(get_app_dir()?.join(CACHE_DIR_NAME))
+}
+
+// 文件名取地址的 SHA-256，不随 Rust 版本变化
+fn cache_file_path(url: &str) -> AppResult<PathBuf> {
+    let digest = Sha256::digest(url.as_bytes());
+    Ok(cache_dir()?.join(format!("{:x}.html", digest)))
+}
+
+// 读取缓存记录和正文，文件丢失时视为没有缓存
+async fn load(url: &str) -> Option<(HttpCacheEntry, String)> {
+    let entry = match storage::get_http_cache_entry(url).await {
+        Ok(entry) => entry?,
+        Err(e) => {
+            warn!("读取页面缓存失败: {}", e);
+            return None;
+        }
+    };
+    let body = fs::read_to_string(&entry.body_path).ok()?;
+    Some((entry, body))
+}
+
+// 先写临时文件再重命名，避免后台刷新时读到写了一半的文件
+async fn store(
+    url: &str,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: &str,
+) -> AppResult<()> {
+    let path = cache_file_path(url)?;
+    fs::create_dir_all(cache_dir()?)?;
+    let temp_path = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp_path, body)?;
+    fs::rename(&temp_path, &path)?;
+
+    storage::save_http_cache_entry(HttpCacheEntry {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        body_path: path.to_string_lossy().to_string(),
+        fetched_at: Utc::now().timestamp_millis(),
+    })
+    .await
+    .map_err(|e| AppError::ExecutionError(format!("写入页面缓存失败: {}", e)))
+}
+
+/// 获取页面文本，优先使用磁盘缓存
+///
+/// 开启 stale-while-revalidate 时直接返回未过期太久的缓存并在后台刷新，
+/// 否则带上 ETag / Last-Modified 发送条件请求，304 时返回缓存内容。
+pub async fn get_text(url: &str) -> AppResult<String> {
+    let stale_while_revalidate = STALE_WHILE_REVALIDATE.load(Ordering::Relaxed);
+
+    match load(url).await {
+        Some((entry, body))
+            if stale_while_revalidate
+                && Utc::now().timestamp_millis() - entry.fetched_at < STALE_MAX_AGE =>
+        {
+            let url = url.to_string();
+            let cached = Some((entry, body.clone()));
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = revalidate(&url, cached).await {
+                    warn!("后台刷新页面缓存失败 {}: {}", url, e);
+                }
+            });
+            Ok(body)
+        }
+        cached => revalidate(url, cached).await,
+    }
+}
+
+/// 获取最新的页面文本，不使用 stale-while-revalidate
+///
+/// 目录同步和线上抓取检查需要服务器上的当前内容，仍发送条件请求，304 时复用缓存正文。
+pub async fn get_fresh_text(url: &str) -> AppResult<String> {
+    revalidate(url, load(url).await).await
+}
+
+/// 读取缓存内容而不访问网络
+pub async fn get_cached_text(url: &str) -> Option<String> {
+    load(url).await.map(|(_, body)| body)
+}
+
+async fn revalidate(url: &str, cached: Option<(HttpCacheEntry, String)>) -> AppResult<String> {
+    let entry = cached.as_ref().map(|(entry, _)| entry);
+    let response = http::send(|client| {
+        let mut request = client.get(url);
+        if let Some(etag) = entry.and_then(|e| e.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = entry.and_then(|e| e.last_modified.as_deref()) {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    })
+    .await?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some((entry, body)) = cached {
+            let now = Utc::now().timestamp_millis();
+            if let Err(e) = storage::touch_http_cache_entry(&entry.url, now).await {
+                warn!("更新页面缓存时间失败: {}", e);
+            }
+            return Ok(body);
+        }
+    }
+
+    let response = response.error_for_status()?;
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let body = response.text().await?;
+
+    if let Err(e) = store(url, etag, last_modified, &body).await {
+        warn!("保存页面缓存失败 {}: {}", url, e);
+    }
+    Ok(body)
+}
@@ -0,0 +1,257 @@
+use crate::api::error::{AppError, AppResult};
+use crate::models::trainer::Trainer;
+use crate::services::http;
+use crate::services::offline;
+use crate::services::settings;
+use crate::services::sources::DEFAULT_SOURCE;
+use crate::services::storage::{self, ImageCacheEntry, ImageKey};
+use crate::utils::path::get_app_dir;
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use log::warn;
+use percent_encoding::percent_decode_str;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use tauri::http::{Response, StatusCode};
+
+/// 缩略图协议名，前端通过 `gmm-img://localhost/<source>/<trainer-id>` 加载
+pub const SCHEME: &str = "gmm-img";
+
+// 缩略图缓存目录名
+const CACHE_DIR_NAME: &str = "images";
+// 预取时的并发下载数
+const PREFETCH_CONCURRENCY: usize = 4;
+// 单张缩略图的大小上限，超过时不缓存
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
+
+// 临时文件序号，预取和协议请求同时写入同一张图片时各用各的临时文件
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn cache_dir() -> AppResult<PathBuf> {
+    Ok(get_app_dir()?.join(CACHE_DIR_NAME))
+}
+
+fn cache_file_path(url: &str) -> AppResult<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    Ok(cache_dir()?.join(format!("{:016x}.img", hasher.finish())))
+}
+
+fn db_error(e: anyhow::Error) -> AppError {
+    AppError::ExecutionError(format!("缩略图缓存数据库操作失败: {}", e))
+}
+
+fn max_cache_bytes() -> u64 {
+    let max_mb = settings::load_settings()
+        .map(|s| s.image_cache_max_mb)
+        .unwrap_or(settings::DEFAULT_IMAGE_CACHE_MAX_MB);
+    max_mb * 1024 * 1024
+}
+
+// 服务器未返回类型时按扩展名推断
+fn guess_content_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    if path.ends_with(".png") {
+        "image/png"
+    } else if path.ends_with(".gif") {
+        "image/gif"
+    } else if path.ends_with(".webp") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    }
+}
+
+/// 预取一页修改器的缩略图
+///
+/// 在后台登记缩略图地址并下载尚未缓存的图片，不阻塞页面加载。
+pub fn prefetch(trainers: &[Trainer]) {
+    let images: Vec<(ImageKey, String)> = trainers
+        .iter()
+        .filter(|t| !t.thumbnail.is_empty())
+        .map(|t| ((t.source.clone(), t.id.clone()), t.thumbnail.clone()))
+        .collect();
+    if images.is_empty() || offline::is_forced() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let pending = match storage::register_image_urls(images).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                warn!("登记缩略图失败: {}", e);
+                return;
+            }
+        };
+
+        stream::iter(pending)
+            .for_each_concurrent(PREFETCH_CONCURRENCY, |(key, url)| async move {
+                if let Err(e) = download(&key, &url).await {
+                    warn!("预取缩略图失败 {}: {}", url, e);
+                }
+            })
+            .await;
+
+        evict().await;
+    });
+}
+
+// 下载并写入缓存，返回图片内容和类型
+async fn download(key: &ImageKey, url: &str) -> AppResult<(Vec<u8>, String)> {
+    let response = http::get(url).await?.error_for_status()?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.starts_with("image/"))
+        .unwrap_or_else(|| guess_content_type(url))
+        .to_string();
+    let too_large = |size: u64| AppError::ValidationError(format!("缩略图过大: {} 字节", size));
+
+    // 服务器声明的大小超限时不下载，没有声明时边读边检查
+    if let Some(length) = response.content_length().filter(|&len| len > MAX_IMAGE_SIZE as u64) {
+        return Err(too_large(length));
+    }
+    let mut bytes = Vec::new();
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > MAX_IMAGE_SIZE {
+            return Err(too_large((bytes.len() + chunk.len()) as u64));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let path = cache_file_path(url)?;
+    fs::create_dir_all(cache_dir()?)?;
+    let temp_path = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp_path, &bytes)?;
+    fs::rename(&temp_path, &path)?;
+
+    storage::save_image_entry(ImageCacheEntry {
+        source: key.0.clone(),
+        trainer_id: key.1.clone(),
+        url: url.to_string(),
+        path: Some(path.to_string_lossy().to_string()),
+        size: bytes.len() as u64,
+        content_type: Some(content_type.clone()),
+        last_access: Utc::now().timestamp_millis(),
+    })
+    .await
+    .map_err(db_error)?;
+
+    Ok((bytes, content_type))
+}
+
+// 超出容量时按最近访问时间淘汰
+async fn evict() {
+    match storage::evict_images(max_cache_bytes()).await {
+        Ok(paths) => {
+            for path in paths {
+                let _ = fs::remove_file(path);
+            }
+        }
+        Err(e) => warn!("清理缩略图缓存失败: {}", e),
+    }
+}
+
+// 读取缩略图，未缓存时下载；`src` 为前端提供的原始地址，用于登记尚未预取的图片
+async fn load(key: &ImageKey, src: Option<String>) -> AppResult<Option<(Vec<u8>, String)>> {
+    let (source, trainer_id) = key;
+    let mut entry = storage::get_image_entry(source, trainer_id)
+        .await
+        .map_err(db_error)?;
+
+    if let Some(src) = src.filter(|src| entry.as_ref().map_or(true, |e| &e.url != src)) {
+        storage::register_image_urls(vec![(key.clone(), src)])
+            .await
+            .map_err(db_error)?;
+        entry = storage::get_image_entry(source, trainer_id)
+            .await
+            .map_err(db_error)?;
+    }
+
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    if let Some(path) = &entry.path {
+        if let Ok(bytes) = fs::read(path) {
+            let now = Utc::now().timestamp_millis();
+            if let Err(e) = storage::touch_image_entry(source, trainer_id, now).await {
+                warn!("更新缩略图访问时间失败: {}", e);
+            }
+            let content_type = entry
+                .content_type
+                .unwrap_or_else(|| guess_content_type(&entry.url).to_string());
+            return Ok(Some((bytes, content_type)));
+        }
+    }
+
+    let image = download(key, &entry.url).await?;
+    evict().await;
+    Ok(Some(image))
+}
+
+fn respond(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .header(CACHE_CONTROL, "max-age=86400")
+        .body(body)
+        .unwrap_or_default()
+}
+
+// 只接受带主机名的 http(s) 地址；本地目录中有该修改器的缩略图时，还必须与之一致
+async fn accept_src(key: &ImageKey, src: String) -> Option<String> {
+    let parsed = url::Url::parse(&src).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return None;
+    }
+    match storage::get_catalog_trainer(key.0.clone(), key.1.clone()).await {
+        Ok(Some(trainer)) if !trainer.thumbnail.is_empty() && trainer.thumbnail != src => {
+            warn!("缩略图地址与目录不一致，已忽略 {}/{}: {}", key.0, key.1, src);
+            None
+        }
+        _ => Some(src),
+    }
+}
+
+/// 处理缩略图协议请求
+///
+/// 路径为 `<来源>/<修改器 ID>`，只有 ID 时视为默认来源，可通过查询参数 `src` 附带原始地址。
+pub async fn handle_request(path: &str, query: Option<&str>) -> Response<Vec<u8>> {
+    let path = percent_decode_str(path.trim_start_matches('/'))
+        .decode_utf8_lossy()
+        .to_string();
+    let key = match path.split_once('/') {
+        Some((source, trainer_id)) => (source.to_string(), trainer_id.to_string()),
+        None => (DEFAULT_SOURCE.to_string(), path),
+    };
+    let src = query.and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "src")
+            .map(|(_, value)| value.to_string())
+    });
+    let src = match src {
+        Some(src) => accept_src(&key, src).await,
+        None => None,
+    };
+
+    match load(&key, src).await {
+        Ok(Some((bytes, content_type))) => respond(StatusCode::OK, &content_type, bytes),
+        Ok(None) => respond(StatusCode::NOT_FOUND, "text/plain", Vec::new()),
+        Err(e) => {
+            warn!("加载缩略图失败 {}/{}: {}", key.0, key.1, e);
+            respond(StatusCode::BAD_GATEWAY, "text/plain", Vec::new())
+        }
+    }
+}
@@ -1,7 +1,18 @@
+pub mod alias;
+pub mod bandwidth;
+pub mod catalog;
 pub mod download_manager;
+pub mod http;
+pub mod http_cache;
+pub mod image_cache;
 pub mod logger;
+pub mod offline;
+pub mod revision;
 pub mod scraper;
+pub mod scraper_health;
+pub mod scraper_rules;
 pub mod settings;
+pub mod sources;
 pub mod storage;
 pub mod trainer;
 pub mod updater;
@@ -0,0 +1,167 @@
+use crate::api::error::{AppError, AppResult};
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::Trainer;
+use crate::services::catalog;
+use crate::services::http_cache;
+use crate::services::settings::{self, OfflineMode};
+use crate::services::sources::{fling::FlingSource, DEFAULT_SOURCE};
+use crate::services::storage;
+use lazy_static::lazy_static;
+use log::warn;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+// 自动模式下最近一次请求是否因网络不可用而失败
+static DETECTED_OFFLINE: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    // 每个请求都要检查离线模式，缓存设置值，保存设置时更新
+    static ref MODE: RwLock<OfflineMode> = RwLock::new(
+        settings::load_settings()
+            .map(|s| s.offline_mode)
+            .unwrap_or_default()
+    );
+}
+
+/// 网络状态
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub mode: OfflineMode,
+    /// 当前是否按离线处理（强制离线或检测到网络不可用）
+    pub offline: bool,
+}
+
+fn mode() -> OfflineMode {
+    *MODE.read().unwrap()
+}
+
+/// 设置变更后更新离线模式
+pub fn apply(mode: OfflineMode) {
+    *MODE.write().unwrap() = mode;
+}
+
+/// 是否在设置中强制离线
+pub fn is_forced() -> bool {
+    mode() == OfflineMode::Forced
+}
+
+pub fn status() -> NetworkStatus {
+    let mode = mode();
+    NetworkStatus {
+        mode,
+        offline: match mode {
+            OfflineMode::Forced => true,
+            OfflineMode::Auto => DETECTED_OFFLINE.load(Ordering::SeqCst),
+            OfflineMode::Disabled => false,
+        },
+    }
+}
+
+/// 连接失败、超时等网络不可用的错误（HTTP 状态码错误不算）
+pub fn is_network_error(error: &AppError) -> bool {
+    match error {
+        AppError::RequestError(e) => e.is_connect() || e.is_timeout(),
+        AppError::OfflineError(_) => true,
+        _ => false,
+    }
+}
+
+/// 执行在线请求，离线时改用本地数据
+///
+/// 强制离线时直接使用 `fallback`；自动模式下遇到网络错误才使用，
+/// 本地数据也不可用时返回原始错误。
+pub async fn run<T, F, Fb>(online: F, fallback: impl FnOnce() -> Fb) -> AppResult<T>
+where
+    F: Future<Output = AppResult<T>>,
+    Fb: Future<Output = AppResult<T>>,
+{
+    let mode = mode();
+    if mode == OfflineMode::Forced {
+        return fallback().await;
+    }
+
+    match online.await {
+        Ok(value) => {
+            DETECTED_OFFLINE.store(false, Ordering::SeqCst);
+            Ok(value)
+        }
+        Err(e) if mode == OfflineMode::Auto && is_network_error(&e) => {
+            DETECTED_OFFLINE.store(true, Ordering::SeqCst);
+            warn!("网络不可用，使用本地数据: {}", e);
+            fallback().await.map_err(|fallback_error| {
+                warn!("本地数据不可用: {}", fallback_error);
+                e
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn fling_source(source: Option<&str>) -> Option<FlingSource> {
+    if source.unwrap_or(DEFAULT_SOURCE) != DEFAULT_SOURCE {
+        return None;
+    }
+    let app_settings = settings::load_settings().ok()?;
+    Some(FlingSource::new(&app_settings.fling_base_url))
+}
+
+fn no_data(what: &str) -> AppError {
+    AppError::OfflineError(format!("没有可用的{}缓存", what))
+}
+
+async fn has_catalog(source: Option<&str>) -> bool {
+    storage::count_catalog(source).await.unwrap_or(0) > 0
+}
+
+// 读取 FLiNG 页面缓存并解析为列表
+async fn cached_fling_list(url: &str, source: &FlingSource) -> AppResult<PaginatedResponse<Trainer>> {
+    let html = http_cache::get_cached_text(url)
+        .await
+        .ok_or_else(|| no_data("列表"))?;
+    source.parse_list_page(&html)
+}
+
+/// 离线列表：优先使用同步的目录，其次使用页面缓存
+pub async fn list(page: u32, source: Option<String>) -> AppResult<PaginatedResponse<Trainer>> {
+    let mut response = if has_catalog(source.as_deref()).await {
+        catalog::get_catalog_trainers(page, source).await?
+    } else {
+        let fling = fling_source(source.as_deref()).ok_or_else(|| no_data("列表"))?;
+        cached_fling_list(&fling.list_url(page), &fling).await?
+    };
+    response.stale = true;
+    Ok(response)
+}
+
+/// 离线搜索：优先使用本地全文索引，其次使用缓存的搜索结果页
+pub async fn search(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let mut response = if has_catalog(source.as_deref()).await {
+        catalog::search_indexed(query, page, source).await?
+    } else {
+        let fling = fling_source(source.as_deref()).ok_or_else(|| no_data("搜索"))?;
+        cached_fling_list(&fling.search_url(&query, page), &fling).await?
+    };
+    response.stale = true;
+    Ok(response)
+}
+
+/// 离线详情：优先使用详情页缓存，其次使用目录中的条目（没有下载项）
+pub async fn detail(id: String, source: Option<String>) -> AppResult<Trainer> {
+    if let Some(fling) = fling_source(source.as_deref()) {
+        if let Some(html) = http_cache::get_cached_text(&fling.detail_url(&id)).await {
+            return fling.parse_detail_page(&html);
+        }
+    }
+
+    let source = source.unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+    storage::get_catalog_trainer(source, id)
+        .await
+        .map_err(|e| AppError::ExecutionError(format!("读取本地目录失败: {}", e)))?
+        .ok_or_else(|| no_data("详情"))
+}
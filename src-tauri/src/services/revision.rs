@@ -0,0 +1,209 @@
+use crate::api::error::{AppError, AppResult};
+use crate::models::revision::{HotkeyChange, TrainerChangelog, TrainerRevision, ValueChange};
+use crate::models::trainer::{InstalledTrainer, Trainer, TrainerOption};
+use crate::services::sources::DEFAULT_SOURCE;
+use crate::services::storage;
+use log::warn;
+
+fn db_error(e: anyhow::Error) -> AppError {
+    AppError::ExecutionError(format!("版本记录数据库操作失败: {}", e))
+}
+
+/// 记录一次抓取结果
+///
+/// 列表页数据没有功能列表，无法和详情页版本比较，不做记录。
+pub async fn record(trainer: &Trainer) {
+    if trainer.options.is_empty() {
+        return;
+    }
+    if let Err(e) = storage::record_trainer_revision(trainer.clone()).await {
+        warn!("记录修改器版本失败 {}: {}", trainer.id, e);
+    }
+}
+
+/// 重新安装前保存旧的安装记录
+///
+/// 安装记录即将被覆盖，即使没有功能列表也要留下旧版本号。
+pub async fn record_installed(installed: &InstalledTrainer) {
+    let trainer = Trainer {
+        id: installed.id.clone(),
+        name: installed.name.clone(),
+        version: installed.version.clone(),
+        game_version: installed.game_version.clone(),
+        download_url: installed.download_url.clone(),
+        description: installed.description.clone(),
+        thumbnail: installed.thumbnail.clone(),
+        download_count: installed.download_count,
+        last_update: installed.last_update.clone(),
+        source: installed.source.clone(),
+        options: installed.options.clone(),
+        downloads: Vec::new(),
+        mirrors: Vec::new(),
+        sha256: None,
+    };
+    if let Err(e) = storage::record_trainer_revision(trainer).await {
+        warn!("记录已安装版本失败 {}: {}", installed.id, e);
+    }
+}
+
+pub async fn list(trainer_id: String, source: Option<String>) -> AppResult<Vec<TrainerRevision>> {
+    let source = source.unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+    storage::get_trainer_revisions(source, trainer_id)
+        .await
+        .map_err(db_error)
+}
+
+/// 生成两个版本之间的更新记录，未指定时比较最近两个版本
+pub async fn changelog(
+    trainer_id: String,
+    source: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> AppResult<TrainerChangelog> {
+    let revisions = list(trainer_id.clone(), source).await?;
+    let find = |id: i64| {
+        revisions
+            .iter()
+            .find(|revision| revision.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFoundError(format!("修改器 {} 的版本 {}", trainer_id, id)))
+    };
+
+    let to = match to {
+        Some(id) => find(id)?,
+        None => revisions
+            .last()
+            .cloned()
+            .ok_or_else(|| AppError::NotFoundError(format!("修改器 {} 的版本记录", trainer_id)))?,
+    };
+    let from = match from {
+        Some(id) => find(id)?,
+        // 只有一个版本时与自身比较，结果为空
+        None => revisions
+            .iter()
+            .rev()
+            .find(|revision| revision.id < to.id)
+            .cloned()
+            .unwrap_or_else(|| to.clone()),
+    };
+
+    Ok(diff(from, to))
+}
+
+fn value_change(from: &str, to: &str) -> Option<ValueChange> {
+    (from != to).then(|| ValueChange {
+        from: from.to_string(),
+        to: to.to_string(),
+    })
+}
+
+// 功能按说明文本对应，忽略大小写和首尾空白
+fn option_key(option: &TrainerOption) -> String {
+    option.label.trim().to_lowercase()
+}
+
+/// 比较两个版本
+pub fn diff(from: TrainerRevision, to: TrainerRevision) -> TrainerChangelog {
+    let find = |options: &[TrainerOption], key: &str| -> Option<TrainerOption> {
+        options.iter().find(|o| option_key(o) == key).cloned()
+    };
+
+    let options_added = to
+        .options
+        .iter()
+        .filter(|o| find(&from.options, &option_key(o)).is_none())
+        .cloned()
+        .collect();
+    let options_removed = from
+        .options
+        .iter()
+        .filter(|o| find(&to.options, &option_key(o)).is_none())
+        .cloned()
+        .collect();
+    let hotkey_changes = to
+        .options
+        .iter()
+        .filter_map(|o| {
+            let old = find(&from.options, &option_key(o))?;
+            (old.hotkey != o.hotkey).then(|| HotkeyChange {
+                label: o.label.clone(),
+                from: old.hotkey,
+                to: o.hotkey.clone(),
+            })
+        })
+        .collect();
+
+    TrainerChangelog {
+        version_change: value_change(&from.version, &to.version),
+        game_version_change: value_change(&from.game_version, &to.game_version),
+        options_added,
+        options_removed,
+        hotkey_changes,
+        from,
+        to,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::trainer::TrainerDate;
+
+    fn option(hotkey: &str, label: &str) -> TrainerOption {
+        TrainerOption {
+            hotkey: hotkey.to_string(),
+            label: label.to_string(),
+            group: None,
+        }
+    }
+
+    fn revision(id: i64, game_version: &str, options: Vec<TrainerOption>) -> TrainerRevision {
+        TrainerRevision {
+            id,
+            source: "fling".to_string(),
+            trainer_id: "elden-ring".to_string(),
+            version: "v1.0".to_string(),
+            game_version: game_version.to_string(),
+            last_update: TrainerDate::parse("2024-03-01"),
+            options,
+            recorded_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_option_and_version_changes() {
+        let from = revision(
+            1,
+            "v1.10",
+            vec![
+                option("Num 1", "Infinite Health"),
+                option("Num 2", "Infinite Stamina"),
+                option("Num 3", "One Hit Kill"),
+            ],
+        );
+        let to = revision(
+            2,
+            "v1.12",
+            vec![
+                option("Num 1", "Infinite Health"),
+                option("Num 4", "infinite stamina "),
+                option("Num 5", "Super Speed"),
+            ],
+        );
+
+        let log = diff(from, to);
+        assert_eq!(
+            log.game_version_change,
+            Some(ValueChange {
+                from: "v1.10".to_string(),
+                to: "v1.12".to_string()
+            })
+        );
+        assert_eq!(log.version_change, None);
+        assert_eq!(log.options_added, [option("Num 5", "Super Speed")]);
+        assert_eq!(log.options_removed, [option("Num 3", "One Hit Kill")]);
+        assert_eq!(log.hotkey_changes.len(), 1);
+        assert_eq!(log.hotkey_changes[0].from, "Num 2");
+        assert_eq!(log.hotkey_changes[0].to, "Num 4");
+    }
+}
@@ -1,34 +1,46 @@
 use crate::api::error::{AppError, AppResult};
-use crate::models::trainer::Trainer;
-use scraper::{Html, Selector};
+use crate::models::trainer::{Trainer, TrainerDate, TrainerDownload, TrainerOption};
+use crate::services::scraper_rules::{self, ScraperRules};
+use crate::services::sources::fling;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+lazy_static! {
+    // 热键，例如 "Num 1"、"Ctrl+Num 1"、"Shift+F1"、"Num ."
+    static ref HOTKEY_RE: Regex = {
+        let key = r"(?:ctrl|alt|shift|num\s*[0-9.+\-*/]|numpad\s*[0-9]|f[0-9]{1,2}|home|end|insert|ins|delete|del|page\s*up|page\s*down|pgup|pgdn|space|tab|enter|[a-z0-9])";
+        Regex::new(&format!(r"(?i)^{key}(?:\s*[+/]\s*{key})*$")).unwrap()
+    };
+}
+
+// 分页信息
+#[derive(Debug, Clone, Default)]
+pub struct PageInfo {
+    pub current_page: u32,
+    pub total_pages: u32,
+    pub per_page: u32,
+    pub has_next: bool,
+}
 
 pub fn parse_trainer_list(html: &str) -> AppResult<Vec<Trainer>> {
+    let rules = scraper_rules::current();
     let document = Html::parse_document(html);
-    let article_selector = Selector::parse("article.post-standard")
-        .map_err(|e| AppError::ParseError(e.to_string()))?;
 
     let mut trainers = Vec::new();
 
-    for article in document.select(&article_selector) {
+    for article in document.select(&rules.list.article) {
         // 提取标题和链接
-        if let Some(title_element) = article
-            .select(&Selector::parse("h2.post-title a").unwrap())
-            .next()
+        if let Some(title_element) = article.select(&rules.list.title).next()
         {
             let name = title_element.text().collect::<String>().trim().to_string();
             let href = title_element.value().attr("href").unwrap_or_default();
 
-            // 从 URL 中提取 ID
-            let id = href
-                .trim_end_matches('/')
-                .split('/')
-                .last()
-                .unwrap_or_default()
-                .to_string();
+            let id = trainer_id_from_url(href);
 
             // 提取缩略图
             let thumbnail = article
-                .select(&Selector::parse(".post-details-thumb img").unwrap())
+                .select(&rules.list.thumbnail)
                 .next()
                 .and_then(|img| img.value().attr("src"))
                 .unwrap_or_default()
@@ -36,7 +48,7 @@ pub fn parse_trainer_list(html: &str) -> AppResult<Vec<Trainer>> {
 
             // 提取版本信息和游戏版本
             let entry_text = article
-                .select(&Selector::parse(".entry").unwrap())
+                .select(&rules.list.entry)
                 .next()
                 .map(|content| content.text().collect::<String>())
                 .unwrap_or_default();
@@ -61,24 +73,24 @@ pub fn parse_trainer_list(html: &str) -> AppResult<Vec<Trainer>> {
 
             // 提取更新日期
             let day = article
-                .select(&Selector::parse(".post-details-day").unwrap())
+                .select(&rules.list.day)
                 .next()
                 .map(|el| el.text().collect::<String>())
                 .unwrap_or_default();
 
             let month = article
-                .select(&Selector::parse(".post-details-month").unwrap())
+                .select(&rules.list.month)
                 .next()
                 .map(|el| el.text().collect::<String>())
                 .unwrap_or_default();
 
             let year = article
-                .select(&Selector::parse(".post-details-year").unwrap())
+                .select(&rules.list.year)
                 .next()
                 .map(|el| el.text().collect::<String>())
                 .unwrap_or_default();
 
-            let last_update = format!("{} {} {}", day, month, year);
+            let last_update = TrainerDate::parse(&format!("{} {} {}", day, month, year));
 
             trainers.push(Trainer {
                 id,
@@ -90,6 +102,11 @@ pub fn parse_trainer_list(html: &str) -> AppResult<Vec<Trainer>> {
                 thumbnail,
                 download_count: 0, // 这个需要从详情页获取
                 last_update,
+                source: fling::SOURCE_ID.to_string(),
+                options: Vec::new(), // 这个需要从详情页获取
+                downloads: Vec::new(),
+                mirrors: Vec::new(),
+                sha256: None,
             });
         }
     }
@@ -97,12 +114,55 @@ pub fn parse_trainer_list(html: &str) -> AppResult<Vec<Trainer>> {
     Ok(trainers)
 }
 
+pub fn parse_pagination(html: &str) -> AppResult<PageInfo> {
+    let rules = scraper_rules::current();
+    let document = Html::parse_document(html);
+
+    let mut info = PageInfo {
+        per_page: document.select(&rules.list.article).count() as u32,
+        ..PageInfo::default()
+    };
+
+    for el in document.select(&rules.pagination.page_number) {
+        if rules.pagination.next.matches(&el) {
+            info.has_next = true;
+            continue;
+        }
+
+        // 页码中可能带千位分隔符，例如 "1,024"
+        let text: String = el
+            .text()
+            .collect::<String>()
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect();
+        if let Ok(number) = text.parse::<u32>() {
+            info.total_pages = info.total_pages.max(number);
+            if rules.pagination.current.matches(&el) {
+                info.current_page = number;
+            }
+        }
+    }
+
+    // 没有分页块时视为只有一页
+    if info.current_page == 0 {
+        info.current_page = 1;
+    }
+    info.total_pages = info.total_pages.max(info.current_page);
+    if !info.has_next {
+        info.has_next = info.current_page < info.total_pages;
+    }
+
+    Ok(info)
+}
+
 pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
+    let rules = scraper_rules::current();
     let document = Html::parse_document(html);
 
     // 提取标题
     let title = document
-        .select(&Selector::parse("h1.post-title").unwrap())
+        .select(&rules.detail.title)
         .next()
         .map(|el| el.text().collect::<String>())
         .ok_or_else(|| AppError::ParseError("Title not found".to_string()))?
@@ -111,7 +171,7 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
 
     // 提取缩略图
     let thumbnail = document
-        .select(&Selector::parse(".entry img.aligncenter").unwrap())
+        .select(&rules.detail.thumbnail)
         .next()
         .and_then(|img| img.value().attr("src"))
         .unwrap_or_default()
@@ -119,7 +179,7 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
 
     // 提取主要内容
     let entry_text = document
-        .select(&Selector::parse(".entry").unwrap())
+        .select(&rules.detail.entry)
         .next()
         .map(|content| {
             // 移除广告div
@@ -127,7 +187,11 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
             let cleaned_html = html
                 .lines()
                 .filter(|line| {
-                    !line.contains("fling-before-content") && !line.contains("fling-after-content")
+                    !rules
+                        .detail
+                        .ad_markers
+                        .iter()
+                        .any(|marker| line.contains(marker.as_str()))
                 })
                 .collect::<Vec<_>>()
                 .join("\n");
@@ -163,7 +227,7 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
     // 提取最后更新时间
     let last_update = parts
         .get(2)
-        .map(|s| s.replace("Last Updated:", "").trim().to_string())
+        .map(|s| TrainerDate::parse(&s.replace("Last Updated:", "")))
         .unwrap_or_default();
 
     // 提取功能列表作为描述
@@ -184,42 +248,23 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
         }
     }
 
-    // 提取下载次数
-    let download_count = document
-        .select(&Selector::parse(".attachment-downloads").unwrap())
-        .next()
-        .map(|el| el.text().collect::<String>())
-        .unwrap_or_default()
-        .parse()
-        .unwrap_or(0);
+    let options = parse_trainer_options(&description);
 
-    // 提取下载链接
-    let download_url = document
-        .select(&Selector::parse(".attachment-link").unwrap())
-        .next()
-        .and_then(|a| a.value().attr("href"))
-        .ok_or_else(|| AppError::ParseError("Download link not found".to_string()))?
-        .to_string();
+    // 提取所有下载附件（不同游戏版本、Steam/Epic 等）
+    let downloads = parse_attachments(&document, &rules);
+    let primary = downloads
+        .first()
+        .ok_or_else(|| AppError::ParseError("Download link not found".to_string()))?;
+    let download_url = primary.url.clone();
+    let download_count = primary.download_count;
 
-    // 从URL中提取ID
+    // 从规范链接中提取ID，与列表页一致
     let id = document
-        .select(&Selector::parse("link[rel='canonical']").unwrap())
+        .select(&rules.detail.canonical)
         .next()
         .and_then(|link| link.value().attr("href"))
-        .map(|url| {
-            url.trim_end_matches('/')
-                .split('/')
-                .last()
-                .unwrap_or_default()
-                .replace("-trainer", "")
-                .to_string()
-        })
-        .unwrap_or_else(|| {
-            title
-                .to_lowercase()
-                .replace(" trainer", "")
-                .replace(' ', "-")
-        });
+        .map(trainer_id_from_url)
+        .unwrap_or_else(|| title.to_lowercase().replace(' ', "-"));
 
     Ok(Trainer {
         id,
@@ -231,5 +276,262 @@ pub fn parse_trainer_detail(html: &str) -> AppResult<Trainer> {
         thumbnail,
         download_count,
         last_update,
+        source: fling::SOURCE_ID.to_string(),
+        options,
+        downloads,
+        mirrors: Vec::new(),
+        sha256: None,
     })
 }
+
+// 解析附件表格，每个附件链接所在的行对应一个下载项
+fn parse_attachments(document: &Html, rules: &ScraperRules) -> Vec<TrainerDownload> {
+    document
+        .select(&rules.attachment.link)
+        .filter_map(|link| {
+            let url = link.value().attr("href")?.trim().to_string();
+            if url.is_empty() {
+                return None;
+            }
+
+            let row = link
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|el| matches!(el.value().name(), "tr" | "li"));
+            let field = |selector: &Selector| {
+                row.and_then(|row| row.select(selector).next())
+                    .map(|el| el.text().collect::<String>().trim().to_string())
+                    .unwrap_or_default()
+            };
+
+            let mut label = link.text().collect::<String>().trim().to_string();
+            if label.is_empty() {
+                label = link.value().attr("title").unwrap_or_default().trim().to_string();
+            }
+
+            // 下载次数可能带有千位分隔符或 "Downloads" 字样
+            let download_count = field(&rules.attachment.downloads)
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0);
+
+            Some(TrainerDownload {
+                label,
+                url,
+                size: field(&rules.attachment.size),
+                date: field(&rules.attachment.date),
+                download_count,
+                mirrors: Vec::new(),
+                sha256: None,
+            })
+        })
+        .collect()
+}
+
+// 从页面链接中提取 ID，例如 ".../trainer/elden-ring-trainer/" 为 "elden-ring-trainer"
+//
+// 列表页和详情页必须使用同一规则，目录按 (来源, ID) 去重。
+fn trainer_id_from_url(url: &str) -> String {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+// 解析功能列表，非热键行视为其后功能项的分组标题
+fn parse_trainer_options(text: &str) -> Vec<TrainerOption> {
+    let mut options = Vec::new();
+    let mut group: Option<String> = None;
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        match split_hotkey_line(line) {
+            Some((hotkey, label)) => options.push(TrainerOption {
+                hotkey,
+                label,
+                group: group.clone(),
+            }),
+            None => {
+                let title = line.trim_end_matches([':', '：']).trim();
+                group = (!title.is_empty()).then(|| title.to_string());
+            }
+        }
+    }
+
+    options
+}
+
+// 拆分 "Num 1 – Infinite Health"，仅有热键的行（如 "Ctrl+Num 1"）说明为空
+fn split_hotkey_line(line: &str) -> Option<(String, String)> {
+    for separator in ['–', '—', '-', ':'] {
+        // 逐个位置尝试，"Num - – Decrease" 中的第一个 '-' 属于热键本身
+        for (index, _) in line.match_indices(separator) {
+            let hotkey = line[..index].trim();
+            if HOTKEY_RE.is_match(hotkey) {
+                let label = line[index + separator.len_utf8()..].trim();
+                return Some((hotkey.to_string(), label.to_string()));
+            }
+        }
+    }
+
+    HOTKEY_RE
+        .is_match(line)
+        .then(|| (line.to_string(), String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_PAGE: &str = include_str!("../../fixtures/fling/list_page.html");
+    const DETAIL_PAGE: &str = include_str!("../../fixtures/fling/detail_page.html");
+    const DETAIL_MULTI_DOWNLOAD: &str =
+        include_str!("../../fixtures/fling/detail_multi_download.html");
+
+    #[test]
+    fn parses_list_page() {
+        let trainers = parse_trainer_list(LIST_PAGE).unwrap();
+        assert_eq!(trainers.len(), 3);
+
+        let first = &trainers[0];
+        assert_eq!(first.id, "elden-ring-trainer");
+        assert_eq!(first.name, "Elden Ring Trainer");
+        assert_eq!(first.version, "53 Options");
+        assert_eq!(first.game_version, "v1.02-v1.10+");
+        assert_eq!(first.last_update.to_string(), "2024-03-12");
+        assert!(first.thumbnail.ends_with("Elden-Ring-150x150.jpg"));
+        assert_eq!(trainers[1].name, "Baldur\u{2019}s Gate 3 Trainer");
+    }
+
+    #[test]
+    fn parses_pagination() {
+        let info = parse_pagination(LIST_PAGE).unwrap();
+        assert_eq!(info.current_page, 2);
+        assert_eq!(info.total_pages, 1024);
+        assert_eq!(info.per_page, 3);
+        assert!(info.has_next);
+    }
+
+    #[test]
+    fn page_without_pagination_is_single_page() {
+        let info = parse_pagination("<html><body></body></html>").unwrap();
+        assert_eq!(info.current_page, 1);
+        assert_eq!(info.total_pages, 1);
+        assert!(!info.has_next);
+    }
+
+    #[test]
+    fn last_page_has_no_next() {
+        let html = r#"
+            <article class="post"></article>
+            <div class="pagination">
+              <a class="prev page-numbers" href="/page/2/">&laquo; Previous</a>
+              <a class="page-numbers" href="/page/1/">1</a>
+              <a class="page-numbers" href="/page/2/">2</a>
+              <span aria-current="page" class="page-numbers current">3</span>
+            </div>
+        "#;
+        let info = parse_pagination(html).unwrap();
+        assert_eq!(info.current_page, 3);
+        assert_eq!(info.total_pages, 3);
+        assert!(!info.has_next);
+    }
+
+    #[test]
+    fn parses_detail_page() {
+        let trainer = parse_trainer_detail(DETAIL_PAGE).unwrap();
+        assert_eq!(trainer.id, "elden-ring-trainer");
+        assert_eq!(trainer.name, "Elden Ring Trainer");
+        assert_eq!(trainer.version, "53 Options");
+        assert_eq!(trainer.game_version, "v1.02-v1.10+");
+        assert_eq!(trainer.last_update.to_string(), "2024-03-12");
+        assert_eq!(trainer.download_url, "https://flingtrainer.com/downloads/41203");
+        assert_eq!(trainer.download_count, 1_234_567);
+        assert!(!trainer.description.contains("/* ad */"));
+    }
+
+    #[test]
+    fn list_and_detail_share_trainer_id() {
+        let listed = parse_trainer_list(LIST_PAGE).unwrap();
+        let detail = parse_trainer_detail(DETAIL_PAGE).unwrap();
+        assert_eq!(listed[0].id, detail.id);
+    }
+
+    #[test]
+    fn parses_detail_options() {
+        let trainer = parse_trainer_detail(DETAIL_PAGE).unwrap();
+        assert_eq!(trainer.options.len(), 8);
+        assert_eq!(
+            trainer.options[0],
+            TrainerOption {
+                hotkey: "Num 1".to_string(),
+                label: "Infinite Health".to_string(),
+                group: None,
+            }
+        );
+        assert_eq!(trainer.options[4].hotkey, "Num .");
+        assert_eq!(trainer.options[5].hotkey, "Ctrl+Num 1");
+        assert_eq!(trainer.options[5].group.as_deref(), Some("Editor Options"));
+        assert_eq!(trainer.options[7].hotkey, "Ctrl+Num 3");
+        assert_eq!(trainer.options[7].label, "");
+    }
+
+    #[test]
+    fn options_text_becomes_grouped_hotkeys() {
+        let options = parse_trainer_options(
+            "Num 1 – Infinite Health\nNum 2: Infinite Stamina\n\nEditor Options:\nCtrl+Num 1 – Edit Runes\nShift+F1\n",
+        );
+        assert_eq!(options.len(), 4);
+        assert_eq!(options[0].hotkey, "Num 1");
+        assert_eq!(options[0].label, "Infinite Health");
+        assert_eq!(options[0].group, None);
+        assert_eq!(options[1].label, "Infinite Stamina");
+        assert_eq!(options[2].hotkey, "Ctrl+Num 1");
+        assert_eq!(options[2].group.as_deref(), Some("Editor Options"));
+        // 只有热键的行说明为空
+        assert_eq!(options[3].hotkey, "Shift+F1");
+        assert_eq!(options[3].label, "");
+    }
+
+    #[test]
+    fn non_hotkey_lines_are_not_options() {
+        assert_eq!(split_hotkey_line("Game Speed - Adjustable"), None);
+        assert_eq!(
+            split_hotkey_line("Num . – Save Location"),
+            Some(("Num .".to_string(), "Save Location".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_all_attachments() {
+        let trainer = parse_trainer_detail(DETAIL_MULTI_DOWNLOAD).unwrap();
+        assert_eq!(trainer.downloads.len(), 3);
+        assert!(trainer.downloads[0].label.ends_with("(Steam)"));
+        assert!(trainer.downloads[1].label.ends_with("(Epic)"));
+        assert_eq!(trainer.downloads[1].url, "https://flingtrainer.com/downloads/38115");
+        assert_eq!(trainer.downloads[1].size, "2 MB");
+        assert_eq!(trainer.downloads[1].date, "2023-12-05");
+        assert_eq!(trainer.downloads[1].download_count, 97_342);
+        // 主下载地址取第一个附件
+        assert_eq!(trainer.download_url, trainer.downloads[0].url);
+    }
+
+    #[test]
+    fn hotkey_with_dash_key() {
+        let trainer = parse_trainer_detail(DETAIL_MULTI_DOWNLOAD).unwrap();
+        let decrease = trainer
+            .options
+            .iter()
+            .find(|option| option.label == "Decrease Game Speed")
+            .unwrap();
+        assert_eq!(decrease.hotkey, "Num -");
+    }
+
+    #[test]
+    fn detail_without_attachments_fails() {
+        let html = r#"<h1 class="post-title">X Trainer</h1><div class="entry"></div>"#;
+        assert!(parse_trainer_detail(html).is_err());
+    }
+}
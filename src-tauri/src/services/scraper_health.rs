@@ -0,0 +1,249 @@
+use crate::api::error::AppResult;
+use crate::models::trainer::{Trainer, TrainerDate};
+use crate::services::scraper;
+use crate::services::settings;
+use crate::services::sources::fling::FlingSource;
+use serde::Serialize;
+
+/// 页面类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageKind {
+    List,
+    Detail,
+}
+
+/// 随应用发布的页面样本（FLiNG 页面结构）
+pub const FIXTURES: &[(&str, PageKind, &str)] = &[
+    (
+        "list_page.html",
+        PageKind::List,
+        include_str!("../../fixtures/fling/list_page.html"),
+    ),
+    (
+        "detail_page.html",
+        PageKind::Detail,
+        include_str!("../../fixtures/fling/detail_page.html"),
+    ),
+    (
+        "detail_multi_download.html",
+        PageKind::Detail,
+        include_str!("../../fixtures/fling/detail_multi_download.html"),
+    ),
+];
+
+/// 单个字段的异常统计
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldIssue {
+    pub field: String,
+    /// 为空或使用默认值的条目数
+    pub empty_count: usize,
+    pub total: usize,
+}
+
+/// 单个页面的检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PageReport {
+    pub name: String,
+    pub kind: PageKind,
+    pub item_count: usize,
+    pub issues: Vec<FieldIssue>,
+    /// 解析直接失败时的错误信息
+    pub error: Option<String>,
+}
+
+impl PageReport {
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none() && self.item_count > 0 && self.issues.is_empty()
+    }
+}
+
+/// 抓取器健康检查报告
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// "live" 或 "fixtures"
+    pub mode: String,
+    pub healthy: bool,
+    pub pages: Vec<PageReport>,
+}
+
+// 字段名 + 判断字段是否为空的函数
+type FieldCheck = (&'static str, fn(&Trainer) -> bool);
+
+// 统计每个字段为空的条目数，只保留有问题的字段
+fn collect_issues(trainers: &[Trainer], checks: &[FieldCheck]) -> Vec<FieldIssue> {
+    checks
+        .iter()
+        .filter_map(|(field, is_empty)| {
+            let empty_count = trainers.iter().filter(|t| is_empty(t)).count();
+            (empty_count > 0).then(|| FieldIssue {
+                field: field.to_string(),
+                empty_count,
+                total: trainers.len(),
+            })
+        })
+        .collect()
+}
+
+fn date_missing(trainer: &Trainer) -> bool {
+    !matches!(trainer.last_update, TrainerDate::Date(_))
+}
+
+/// 检查列表页
+pub fn check_list_page(name: &str, html: &str) -> PageReport {
+    let trainers = match scraper::parse_trainer_list(html) {
+        Ok(trainers) => trainers,
+        Err(e) => return failed_report(name, PageKind::List, e.to_string()),
+    };
+
+    let mut issues = collect_issues(
+        &trainers,
+        &[
+            ("id", |t| t.id.is_empty()),
+            ("name", |t| t.name.is_empty()),
+            ("version", |t| t.version.is_empty()),
+            ("game_version", |t| t.game_version.is_empty()),
+            ("thumbnail", |t| t.thumbnail.is_empty()),
+            ("last_update", date_missing),
+        ],
+    );
+
+    // 列表页应当能解析出分页块
+    match scraper::parse_pagination(html) {
+        Ok(info) if info.total_pages > 1 => {}
+        _ => issues.push(FieldIssue {
+            field: "pagination".to_string(),
+            empty_count: 1,
+            total: 1,
+        }),
+    }
+
+    PageReport {
+        name: name.to_string(),
+        kind: PageKind::List,
+        item_count: trainers.len(),
+        issues,
+        error: None,
+    }
+}
+
+/// 检查详情页
+pub fn check_detail_page(name: &str, html: &str) -> PageReport {
+    let trainer = match scraper::parse_trainer_detail(html) {
+        Ok(trainer) => trainer,
+        Err(e) => return failed_report(name, PageKind::Detail, e.to_string()),
+    };
+
+    let trainers = [trainer];
+    let issues = collect_issues(
+        &trainers,
+        &[
+            ("id", |t| t.id.is_empty()),
+            ("name", |t| t.name.is_empty()),
+            ("version", |t| t.version.is_empty()),
+            ("game_version", |t| t.game_version.is_empty()),
+            ("thumbnail", |t| t.thumbnail.is_empty()),
+            ("last_update", date_missing),
+            ("description", |t| t.description.is_empty()),
+            ("options", |t| t.options.is_empty()),
+            ("downloads", |t| t.downloads.is_empty()),
+            ("download_count", |t| t.download_count == 0),
+        ],
+    );
+
+    PageReport {
+        name: name.to_string(),
+        kind: PageKind::Detail,
+        item_count: trainers.len(),
+        issues,
+        error: None,
+    }
+}
+
+fn failed_report(name: &str, kind: PageKind, error: String) -> PageReport {
+    PageReport {
+        name: name.to_string(),
+        kind,
+        item_count: 0,
+        issues: Vec::new(),
+        error: Some(error),
+    }
+}
+
+fn build_report(mode: &str, pages: Vec<PageReport>) -> HealthReport {
+    HealthReport {
+        mode: mode.to_string(),
+        healthy: pages.iter().all(PageReport::is_healthy),
+        pages,
+    }
+}
+
+/// 使用内置页面样本检查解析规则
+pub fn check_fixtures() -> HealthReport {
+    let pages = FIXTURES
+        .iter()
+        .map(|(name, kind, html)| match kind {
+            PageKind::List => check_list_page(name, html),
+            PageKind::Detail => check_detail_page(name, html),
+        })
+        .collect();
+    build_report("fixtures", pages)
+}
+
+/// 抓取线上首页和第一个修改器的详情页进行检查
+pub async fn check_live() -> AppResult<HealthReport> {
+    let app_settings = settings::load_settings()?;
+    let source = FlingSource::new(&app_settings.fling_base_url);
+    let mut pages = Vec::new();
+
+    let list_url = source.list_url(1);
+    let list_html = source.fetch_fresh_html(&list_url).await?;
+    pages.push(check_list_page(&list_url, &list_html));
+
+    let first_id = scraper::parse_trainer_list(&list_html)
+        .ok()
+        .and_then(|trainers| trainers.into_iter().next())
+        .map(|trainer| trainer.id);
+    if let Some(id) = first_id {
+        let detail_url = source.detail_url(&id);
+        let detail_html = source.fetch_fresh_html(&detail_url).await?;
+        pages.push(check_detail_page(&detail_url, &detail_html));
+    }
+
+    Ok(build_report("live", pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixtures_report_healthy() {
+        let report = check_fixtures();
+        for page in &report.pages {
+            assert!(page.is_healthy(), "{} 存在问题: {:?}", page.name, page);
+        }
+        assert!(report.healthy);
+    }
+
+    #[test]
+    fn broken_page_reports_empty_fields() {
+        let html = r#"<article class="post-standard"><h2 class="post-title"><a href="/trainer/x/">X</a></h2></article>"#;
+        let report = check_list_page("broken", html);
+
+        assert!(!report.is_healthy());
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert!(fields.contains(&"thumbnail"));
+        assert!(fields.contains(&"last_update"));
+        assert!(fields.contains(&"pagination"));
+    }
+
+    #[test]
+    fn detail_without_attachments_reports_error() {
+        let html = r#"<h1 class="post-title">X Trainer</h1><div class="entry"></div>"#;
+        let report = check_detail_page("broken", html);
+
+        assert!(report.error.is_some());
+        assert!(!report.is_healthy());
+    }
+}
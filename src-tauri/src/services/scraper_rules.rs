@@ -0,0 +1,230 @@
+use crate::api::error::{AppError, AppResult};
+use crate::utils::path::get_app_dir;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use scraper::Selector;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+// 随应用发布的默认规则
+const BUNDLED_RULES: &str = include_str!("../../resources/scraper_rules.json");
+// 应用数据目录中的覆盖文件名
+const OVERRIDE_FILE_NAME: &str = "scraper_rules.json";
+
+lazy_static! {
+    static ref RULES: RwLock<Arc<ScraperRules>> = RwLock::new(Arc::new(
+        ScraperRules::from_json(BUNDLED_RULES).expect("内置选择器规则无效")
+    ));
+}
+
+/// 选择器规则文件（JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleFile {
+    pub version: u32,
+    pub list: ListRuleFile,
+    pub pagination: PaginationRuleFile,
+    pub detail: DetailRuleFile,
+    pub attachment: AttachmentRuleFile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListRuleFile {
+    pub article: String,
+    pub title: String,
+    pub thumbnail: String,
+    pub entry: String,
+    pub day: String,
+    pub month: String,
+    pub year: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationRuleFile {
+    pub page_number: String,
+    pub current: String,
+    pub next: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailRuleFile {
+    pub title: String,
+    pub thumbnail: String,
+    pub entry: String,
+    pub canonical: String,
+    /// 广告块标记，包含这些文本的行会在解析前被移除
+    #[serde(default)]
+    pub ad_markers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentRuleFile {
+    pub link: String,
+    pub size: String,
+    pub date: String,
+    pub downloads: String,
+}
+
+/// 编译后的选择器规则
+pub struct ScraperRules {
+    pub version: u32,
+    pub list: ListRules,
+    pub pagination: PaginationRules,
+    pub detail: DetailRules,
+    pub attachment: AttachmentRules,
+}
+
+pub struct ListRules {
+    pub article: Selector,
+    pub title: Selector,
+    pub thumbnail: Selector,
+    pub entry: Selector,
+    pub day: Selector,
+    pub month: Selector,
+    pub year: Selector,
+}
+
+pub struct PaginationRules {
+    pub page_number: Selector,
+    pub current: Selector,
+    pub next: Selector,
+}
+
+pub struct DetailRules {
+    pub title: Selector,
+    pub thumbnail: Selector,
+    pub entry: Selector,
+    pub canonical: Selector,
+    pub ad_markers: Vec<String>,
+}
+
+pub struct AttachmentRules {
+    pub link: Selector,
+    pub size: Selector,
+    pub date: Selector,
+    pub downloads: Selector,
+}
+
+// 编译单条规则，出错时带上规则名
+fn compile(rule: &str, selector: &str) -> AppResult<Selector> {
+    Selector::parse(selector).map_err(|e| {
+        AppError::ParseError(format!("选择器规则 {} 无效 ({}): {}", rule, selector, e))
+    })
+}
+
+impl ScraperRules {
+    pub fn from_json(content: &str) -> AppResult<Self> {
+        let file: RuleFile = serde_json::from_str(content)
+            .map_err(|e| AppError::ParseError(format!("选择器规则文件格式错误: {}", e)))?;
+        Self::compile(&file)
+    }
+
+    pub fn compile(file: &RuleFile) -> AppResult<Self> {
+        Ok(Self {
+            version: file.version,
+            list: ListRules {
+                article: compile("list.article", &file.list.article)?,
+                title: compile("list.title", &file.list.title)?,
+                thumbnail: compile("list.thumbnail", &file.list.thumbnail)?,
+                entry: compile("list.entry", &file.list.entry)?,
+                day: compile("list.day", &file.list.day)?,
+                month: compile("list.month", &file.list.month)?,
+                year: compile("list.year", &file.list.year)?,
+            },
+            pagination: PaginationRules {
+                page_number: compile("pagination.page_number", &file.pagination.page_number)?,
+                current: compile("pagination.current", &file.pagination.current)?,
+                next: compile("pagination.next", &file.pagination.next)?,
+            },
+            detail: DetailRules {
+                title: compile("detail.title", &file.detail.title)?,
+                thumbnail: compile("detail.thumbnail", &file.detail.thumbnail)?,
+                entry: compile("detail.entry", &file.detail.entry)?,
+                canonical: compile("detail.canonical", &file.detail.canonical)?,
+                ad_markers: file.detail.ad_markers.clone(),
+            },
+            attachment: AttachmentRules {
+                link: compile("attachment.link", &file.attachment.link)?,
+                size: compile("attachment.size", &file.attachment.size)?,
+                date: compile("attachment.date", &file.attachment.date)?,
+                downloads: compile("attachment.downloads", &file.attachment.downloads)?,
+            },
+        })
+    }
+}
+
+/// 覆盖规则文件路径
+pub fn override_path() -> AppResult<PathBuf> {
+    Ok(get_app_dir()?.join(OVERRIDE_FILE_NAME))
+}
+
+/// 获取当前生效的规则
+pub fn current() -> Arc<ScraperRules> {
+    RULES.read().unwrap().clone()
+}
+
+/// 加载规则：存在覆盖文件且版本不低于内置规则时使用覆盖文件
+///
+/// 覆盖文件无效时返回错误，当前规则保持为内置规则。
+pub fn load() -> AppResult<u32> {
+    let path = override_path()?;
+    let content = if path.exists() {
+        Some(fs::read_to_string(&path)?)
+    } else {
+        None
+    };
+    apply(content.as_deref())
+}
+
+// 按覆盖文件内容更新当前规则，没有覆盖文件时使用内置规则
+fn apply(content: Option<&str>) -> AppResult<u32> {
+    let bundled = ScraperRules::from_json(BUNDLED_RULES)?;
+
+    let rules = match content.map(ScraperRules::from_json) {
+        Some(Ok(rules)) if rules.version >= bundled.version => {
+            info!("使用覆盖的选择器规则 v{}", rules.version);
+            rules
+        }
+        Some(Ok(rules)) => {
+            warn!(
+                "覆盖的选择器规则版本过旧 (v{} < v{})，使用内置规则",
+                rules.version, bundled.version
+            );
+            bundled
+        }
+        Some(Err(e)) => {
+            *RULES.write().unwrap() = Arc::new(bundled);
+            return Err(e);
+        }
+        None => bundled,
+    };
+
+    let version = rules.version;
+    *RULES.write().unwrap() = Arc::new(rules);
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scraper;
+
+    #[test]
+    fn invalid_override_names_rule_and_keeps_bundled() {
+        let mut file: RuleFile = serde_json::from_str(BUNDLED_RULES).unwrap();
+        file.version += 1;
+        file.list.title = "h2[".to_string();
+        let content = serde_json::to_string(&file).unwrap();
+
+        let error = apply(Some(&content)).unwrap_err().to_string();
+        assert!(error.contains("list.title"), "{}", error);
+
+        // 仍使用内置规则解析列表页
+        let bundled: RuleFile = serde_json::from_str(BUNDLED_RULES).unwrap();
+        assert_eq!(current().version, bundled.version);
+        let trainers =
+            scraper::parse_trainer_list(include_str!("../../fixtures/fling/list_page.html")).unwrap();
+        assert!(!trainers.is_empty());
+    }
+}
@@ -17,6 +17,127 @@ pub struct AppSettings {
     pub auto_open_folder: bool,
     /// 语言设置
     pub language: String,
+    /// FLiNG 站点地址，可指向镜像、缓存代理或本地测试服务器
+    #[serde(default = "default_fling_base_url")]
+    pub fling_base_url: String,
+    /// 自定义修改器来源（JSON 索引）
+    #[serde(default)]
+    pub custom_sources: Vec<CustomSourceConfig>,
+    /// 启动时是否在后台同步修改器目录
+    #[serde(default = "default_true")]
+    pub auto_sync_catalog: bool,
+    /// 网络代理，用于抓取、修改器下载和更新下载
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// 页面缓存策略：开启时先显示缓存内容，再在后台刷新
+    #[serde(default = "default_true")]
+    pub stale_while_revalidate: bool,
+    /// 离线模式
+    #[serde(default)]
+    pub offline_mode: OfflineMode,
+    /// 缩略图缓存容量上限（MB），超出时淘汰最久未使用的图片
+    #[serde(default = "default_image_cache_max_mb")]
+    pub image_cache_max_mb: u64,
+    /// 同时进行的最大下载数，其余下载排队等待
+    #[serde(default = "default_max_parallel_downloads")]
+    pub max_parallel_downloads: u32,
+    /// 启动时自动继续上次未完成的下载，关闭时先询问
+    #[serde(default = "default_true")]
+    pub auto_resume_downloads: bool,
+    /// 下载限速
+    #[serde(default)]
+    pub bandwidth: BandwidthSettings,
+    /// 服务器支持分段请求时，单个下载使用的连接数（1 表示不分段）
+    #[serde(default = "default_download_segments")]
+    pub download_segments: u32,
+    /// 下载地址改写模板，原地址失败后按顺序尝试，支持 `{url}`、`{encoded_url}`、`{path}`、`{filename}`
+    #[serde(default)]
+    pub download_mirror_templates: Vec<String>,
+}
+
+/// 下载限速设置，速度单位为 KB/s，0 表示不限速
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BandwidthSettings {
+    /// 所有下载合计的速度上限
+    pub limit_kbps: u64,
+    /// 单个下载的速度上限
+    pub per_download_limit_kbps: u64,
+    /// 是否在指定时段（例如夜间）不限速
+    pub unlimited_schedule: bool,
+    /// 不限速时段的开始时间（HH:MM，本地时间）
+    pub unlimited_from: String,
+    /// 不限速时段的结束时间（HH:MM），早于开始时间时表示跨过午夜
+    pub unlimited_to: String,
+}
+
+impl Default for BandwidthSettings {
+    fn default() -> Self {
+        Self {
+            limit_kbps: 0,
+            per_download_limit_kbps: 0,
+            unlimited_schedule: false,
+            unlimited_from: "23:00".to_string(),
+            unlimited_to: "07:00".to_string(),
+        }
+    }
+}
+
+/// 离线模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OfflineMode {
+    /// 网络不可用时自动使用缓存数据
+    #[default]
+    Auto,
+    /// 始终离线，不发起任何网络请求
+    Forced,
+    /// 不使用缓存数据兜底
+    Disabled,
+}
+
+/// 代理模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// 不使用代理
+    None,
+    /// 使用系统代理（环境变量和系统设置）
+    #[default]
+    System,
+    Http,
+    Socks5,
+}
+
+/// 代理设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 不走代理的主机，例如 "localhost"、"*.lan"、"192.168.0.0/16"
+    pub bypass: Vec<String>,
+}
+
+impl ProxySettings {
+    /// 是否配置了手动代理（HTTP 或 SOCKS5）
+    pub fn is_manual(&self) -> bool {
+        matches!(self.mode, ProxyMode::Http | ProxyMode::Socks5)
+    }
+}
+
+/// 自定义 JSON 索引来源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomSourceConfig {
+    /// 来源标识，需唯一
+    pub id: String,
+    /// 展示名称
+    pub name: String,
+    /// 索引地址（http(s) 或 file://）
+    pub index_url: String,
 }
 
 impl Default for AppSettings {
@@ -32,10 +153,104 @@ impl Default for AppSettings {
             auto_extract: true,
             auto_open_folder: false,
             language: "zh-CN".to_string(),
+            fling_base_url: default_fling_base_url(),
+            custom_sources: Vec::new(),
+            auto_sync_catalog: true,
+            proxy: ProxySettings::default(),
+            stale_while_revalidate: true,
+            offline_mode: OfflineMode::default(),
+            image_cache_max_mb: DEFAULT_IMAGE_CACHE_MAX_MB,
+            max_parallel_downloads: DEFAULT_MAX_PARALLEL_DOWNLOADS,
+            auto_resume_downloads: true,
+            bandwidth: BandwidthSettings::default(),
+            download_segments: DEFAULT_DOWNLOAD_SEGMENTS,
+            download_mirror_templates: Vec::new(),
         }
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
+/// 缩略图缓存默认容量（MB）
+pub const DEFAULT_IMAGE_CACHE_MAX_MB: u64 = 200;
+
+fn default_image_cache_max_mb() -> u64 {
+    DEFAULT_IMAGE_CACHE_MAX_MB
+}
+
+/// 默认最大并行下载数
+pub const DEFAULT_MAX_PARALLEL_DOWNLOADS: u32 = 3;
+
+fn default_max_parallel_downloads() -> u32 {
+    DEFAULT_MAX_PARALLEL_DOWNLOADS
+}
+
+/// 默认分段下载连接数
+pub const DEFAULT_DOWNLOAD_SEGMENTS: u32 = 4;
+/// 分段下载连接数上限
+pub const MAX_DOWNLOAD_SEGMENTS: u32 = 16;
+
+fn default_download_segments() -> u32 {
+    DEFAULT_DOWNLOAD_SEGMENTS
+}
+
+fn default_fling_base_url() -> String {
+    crate::services::sources::fling::BASE_URL.to_string()
+}
+
+/// 校验设置项
+fn validate_settings(settings: &AppSettings) -> AppResult<()> {
+    let base_url = url::Url::parse(&settings.fling_base_url)
+        .map_err(|e| AppError::ConfigError(format!("站点地址无效: {}", e)))?;
+    if !matches!(base_url.scheme(), "http" | "https") {
+        return Err(AppError::ConfigError(format!(
+            "站点地址仅支持 http/https: {}",
+            settings.fling_base_url
+        )));
+    }
+
+    if settings.max_parallel_downloads == 0 {
+        return Err(AppError::ConfigError("最大并行下载数至少为 1".to_string()));
+    }
+
+    if !(1..=MAX_DOWNLOAD_SEGMENTS).contains(&settings.download_segments) {
+        return Err(AppError::ConfigError(format!(
+            "分段下载连接数应在 1 到 {} 之间",
+            MAX_DOWNLOAD_SEGMENTS
+        )));
+    }
+
+    for template in &settings.download_mirror_templates {
+        let template = template.trim();
+        let valid_scheme = template.starts_with("http://") || template.starts_with("https://");
+        if !valid_scheme || !template.contains('{') {
+            return Err(AppError::ConfigError(format!(
+                "镜像地址模板无效，应为包含占位符的 http/https 地址: {}",
+                template
+            )));
+        }
+    }
+
+    let bandwidth = &settings.bandwidth;
+    if bandwidth.unlimited_schedule {
+        for time in [&bandwidth.unlimited_from, &bandwidth.unlimited_to] {
+            chrono::NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+                AppError::ConfigError(format!("不限速时段格式应为 HH:MM: {}", time))
+            })?;
+        }
+    }
+
+    let proxy = &settings.proxy;
+    if proxy.is_manual() && (proxy.host.trim().is_empty() || proxy.port == 0) {
+        return Err(AppError::ConfigError(
+            "代理服务器地址和端口不能为空".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// 获取默认下载路径
 fn get_default_download_path() -> AppResult<PathBuf> {
     Ok(get_app_dir()?.join("downloads"))
@@ -67,6 +282,8 @@ pub fn load_settings() -> AppResult<AppSettings> {
 
 /// 保存应用设置
 pub fn save_settings(settings: &AppSettings) -> AppResult<()> {
+    validate_settings(settings)?;
+
     let settings_path = get_settings_file_path()?;
     
     // 确保父目录存在
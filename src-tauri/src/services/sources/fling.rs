@@ -0,0 +1,180 @@
+use super::TrainerSource;
+use crate::api::error::{AppError, AppResult};
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::Trainer;
+use crate::services::http_cache;
+use crate::services::scraper;
+use async_trait::async_trait;
+
+pub const SOURCE_ID: &str = "fling";
+
+pub const BASE_URL: &str = "https://flingtrainer.com";
+
+/// FLiNG 网站来源，通过抓取 HTML 页面获取数据
+pub struct FlingSource {
+    base_url: String,
+}
+
+impl FlingSource {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn list_url(&self, page: u32) -> String {
+        format!("{}/page/{}/", self.base_url, page)
+    }
+
+    pub fn detail_url(&self, id: &str) -> String {
+        format!("{}/trainer/{}/", self.base_url, id)
+    }
+
+    pub fn search_url(&self, query: &str, page: u32) -> String {
+        let query: String = url::form_urlencoded::byte_serialize(query.as_bytes()).collect();
+        format!("{}/page/{}/?s={}", self.base_url, page, query)
+    }
+
+    pub async fn fetch_html(&self, url: &str) -> AppResult<String> {
+        http_cache::get_text(url).await
+    }
+
+    /// 跳过 stale-while-revalidate，向服务器确认后再返回页面
+    pub async fn fetch_fresh_html(&self, url: &str) -> AppResult<String> {
+        http_cache::get_fresh_text(url).await
+    }
+
+    async fn fetch_list(&self, url: &str) -> AppResult<PaginatedResponse<Trainer>> {
+        let html = self.fetch_html(url).await?;
+        self.parse_list_page(&html)
+    }
+
+    /// 解析列表页（搜索结果页结构相同）
+    pub fn parse_list_page(&self, html: &str) -> AppResult<PaginatedResponse<Trainer>> {
+        let mut trainers = scraper::parse_trainer_list(html)?;
+        let page_info = scraper::parse_pagination(html)?;
+
+        for trainer in &mut trainers {
+            trainer.thumbnail = self.absolute_url(&trainer.thumbnail);
+        }
+
+        Ok(paginate(trainers, page_info))
+    }
+
+    /// 解析详情页并补全链接
+    pub fn parse_detail_page(&self, html: &str) -> AppResult<Trainer> {
+        let mut trainer = scraper::parse_trainer_detail(html)?;
+        trainer.thumbnail = self.absolute_url(&trainer.thumbnail);
+        trainer.download_url = self.absolute_url(&trainer.download_url);
+        for download in &mut trainer.downloads {
+            download.url = self.absolute_url(&download.url);
+        }
+        Ok(trainer)
+    }
+
+    // 镜像或本地服务器可能返回相对链接，统一按站点地址补全
+    fn absolute_url(&self, link: &str) -> String {
+        if link.is_empty() {
+            return String::new();
+        }
+        url::Url::parse(&format!("{}/", self.base_url))
+            .and_then(|base| base.join(link))
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| link.to_string())
+    }
+}
+
+#[async_trait]
+impl TrainerSource for FlingSource {
+    fn id(&self) -> &str {
+        SOURCE_ID
+    }
+
+    fn name(&self) -> &str {
+        "FLiNG Trainer"
+    }
+
+    fn sorted_by_date(&self) -> bool {
+        true
+    }
+
+    async fn list(&self, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        self.fetch_list(&self.list_url(page)).await
+    }
+
+    async fn sync_list(&self, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        let html = self.fetch_fresh_html(&self.list_url(page)).await?;
+        self.parse_list_page(&html)
+    }
+
+    async fn search(&self, query: &str, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        self.fetch_list(&self.search_url(query, page)).await
+    }
+
+    async fn detail(&self, id: &str) -> AppResult<Trainer> {
+        let html = self.fetch_html(&self.detail_url(id)).await?;
+        self.parse_detail_page(&html)
+    }
+
+    async fn resolve_download(&self, trainer: &Trainer) -> AppResult<String> {
+        if !trainer.download_url.is_empty() {
+            return Ok(trainer.download_url.clone());
+        }
+
+        // 列表页不含下载地址，需要从详情页获取
+        let detail = self.detail(&trainer.id).await?;
+        if detail.download_url.is_empty() {
+            return Err(AppError::NotFoundError("下载链接".to_string()));
+        }
+        Ok(detail.download_url)
+    }
+}
+
+// 根据分页信息组装响应
+fn paginate(trainers: Vec<Trainer>, page_info: scraper::PageInfo) -> PaginatedResponse<Trainer> {
+    // 网站不直接给出总条数：不在末页时按 "末页前的满页 + 半页" 估算末页条数，
+    // 在末页时按 "之前的满页 + 当前页实际条数" 计算
+    let per_page = page_info.per_page.max(trainers.len() as u32);
+    let total = if page_info.has_next {
+        page_info.total_pages.saturating_sub(1) * per_page + per_page.div_ceil(2)
+    } else {
+        (page_info.current_page - 1) * per_page + trainers.len() as u32
+    };
+
+    PaginatedResponse {
+        trainers,
+        total,
+        total_pages: page_info.total_pages,
+        current_page: page_info.current_page,
+        has_next: page_info.has_next,
+        stale: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(current_page: u32, total_pages: u32, has_next: bool) -> scraper::PageInfo {
+        scraper::PageInfo {
+            current_page,
+            total_pages,
+            per_page: 15,
+            has_next,
+        }
+    }
+
+    #[test]
+    fn total_is_estimated_from_pagination() {
+        // 末页条数未知，按半页估算
+        let middle = paginate(Vec::new(), page(2, 40, true));
+        assert_eq!(middle.total, 39 * 15 + 8);
+        assert_eq!(middle.current_page, 2);
+        assert!(middle.has_next);
+
+        // 末页按实际条数计算
+        let last = paginate(Vec::new(), page(40, 40, false));
+        assert_eq!(last.total, 39 * 15);
+        assert!(!last.has_next);
+    }
+}
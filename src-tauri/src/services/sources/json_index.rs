@@ -0,0 +1,199 @@
+use super::TrainerSource;
+use crate::api::error::{AppError, AppResult};
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::{Trainer, TrainerDate, TrainerDownload, TrainerOption};
+use crate::services::http;
+use crate::services::settings::CustomSourceConfig;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 每页条数
+const PAGE_SIZE: usize = 20;
+// 解析后的索引在内存中保留的时间，同步目录时逐页读取不会重复下载
+const INDEX_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+// 缓存的索引内容和读取时间
+type CachedIndex = (Instant, Arc<Vec<Trainer>>);
+
+lazy_static! {
+    // 按（来源, 索引地址）缓存
+    static ref INDEX_CACHE: Mutex<HashMap<(String, String), CachedIndex>> = Mutex::new(HashMap::new());
+}
+
+/// JSON 索引来源
+///
+/// 索引地址可以是 http(s) 或 file:// URL，内容为条目数组，或 `{ "trainers": [...] }`。
+pub struct JsonIndexSource {
+    config: CustomSourceConfig,
+}
+
+// 索引条目，除 id 和 name 外的字段均可省略
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IndexEntry {
+    id: String,
+    name: String,
+    version: String,
+    game_version: String,
+    download_url: String,
+    description: String,
+    thumbnail: String,
+    download_count: i32,
+    last_update: TrainerDate,
+    options: Vec<TrainerOption>,
+    downloads: Vec<TrainerDownload>,
+    mirrors: Vec<String>,
+    sha256: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IndexDocument {
+    List(Vec<IndexEntry>),
+    Wrapped { trainers: Vec<IndexEntry> },
+}
+
+impl JsonIndexSource {
+    pub fn new(config: CustomSourceConfig) -> Self {
+        Self { config }
+    }
+
+    // 读取索引，优先使用未过期的缓存
+    async fn load_index(&self) -> AppResult<Arc<Vec<Trainer>>> {
+        let key = (self.config.id.clone(), self.config.index_url.clone());
+        if let Some((loaded_at, trainers)) = INDEX_CACHE.lock().unwrap().get(&key) {
+            if loaded_at.elapsed() < INDEX_CACHE_TTL {
+                return Ok(trainers.clone());
+            }
+        }
+
+        let trainers = Arc::new(self.fetch_index().await?);
+        INDEX_CACHE
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), trainers.clone()));
+        Ok(trainers)
+    }
+
+    // 下载并解析整个索引
+    async fn fetch_index(&self) -> AppResult<Vec<Trainer>> {
+        let url = url::Url::parse(&self.config.index_url)
+            .map_err(|e| AppError::ConfigError(format!("索引地址无效: {}", e)))?;
+
+        let content = if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|_| AppError::ConfigError(format!("索引路径无效: {}", url)))?;
+            fs::read_to_string(path)?
+        } else {
+            let response = http::get(url.as_str()).await?;
+            if !response.status().is_success() {
+                return Err(AppError::DownloadError(format!(
+                    "获取索引失败，HTTP状态码: {}",
+                    response.status()
+                )));
+            }
+            response.text().await?
+        };
+
+        let entries = match serde_json::from_str::<IndexDocument>(&content)? {
+            IndexDocument::List(entries) => entries,
+            IndexDocument::Wrapped { trainers } => trainers,
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| !entry.id.is_empty() && !entry.name.is_empty())
+            .map(|entry| Trainer {
+                id: entry.id,
+                name: entry.name,
+                version: entry.version,
+                game_version: entry.game_version,
+                download_url: entry.download_url,
+                description: entry.description,
+                thumbnail: entry.thumbnail,
+                download_count: entry.download_count,
+                last_update: entry.last_update,
+                source: self.config.id.clone(),
+                options: entry.options,
+                downloads: entry.downloads,
+                mirrors: entry.mirrors,
+                sha256: entry.sha256,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl TrainerSource for JsonIndexSource {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn list(&self, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        Ok(paginate(&self.load_index().await?, page))
+    }
+
+    async fn search(&self, query: &str, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        let query = query.trim().to_lowercase();
+        let matched: Vec<Trainer> = self
+            .load_index()
+            .await?
+            .iter()
+            .filter(|trainer| trainer.name.to_lowercase().contains(&query))
+            .cloned()
+            .collect();
+        Ok(paginate(&matched, page))
+    }
+
+    async fn detail(&self, id: &str) -> AppResult<Trainer> {
+        self.load_index()
+            .await?
+            .iter()
+            .find(|trainer| trainer.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFoundError(format!("修改器 {}", id)))
+    }
+
+    async fn resolve_download(&self, trainer: &Trainer) -> AppResult<String> {
+        if !trainer.download_url.is_empty() {
+            return Ok(trainer.download_url.clone());
+        }
+        let detail = self.detail(&trainer.id).await?;
+        if detail.download_url.is_empty() {
+            return Err(AppError::NotFoundError("下载链接".to_string()));
+        }
+        Ok(detail.download_url)
+    }
+}
+
+// 在内存中分页
+fn paginate(trainers: &[Trainer], page: u32) -> PaginatedResponse<Trainer> {
+    let page = page.max(1);
+    let total = trainers.len();
+    let total_pages = total.div_ceil(PAGE_SIZE).max(1) as u32;
+    let page_items = trainers
+        .iter()
+        .skip((page as usize - 1) * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .cloned()
+        .collect();
+
+    PaginatedResponse {
+        trainers: page_items,
+        total: total as u32,
+        total_pages,
+        current_page: page,
+        has_next: page < total_pages,
+        stale: false,
+    }
+}
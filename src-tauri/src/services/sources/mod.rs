@@ -0,0 +1,131 @@
+pub mod fling;
+pub mod json_index;
+
+use crate::api::error::{AppError, AppResult};
+use crate::api::trainer::PaginatedResponse;
+use crate::models::trainer::Trainer;
+use crate::services::settings;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use log::warn;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 默认来源（FLiNG）
+pub const DEFAULT_SOURCE: &str = fling::SOURCE_ID;
+
+/// 修改器目录来源
+///
+/// 每个来源负责列表、搜索、详情和下载地址解析，返回的修改器需填写 `source` 字段。
+#[async_trait]
+pub trait TrainerSource: Send + Sync {
+    /// 来源唯一标识
+    fn id(&self) -> &str;
+
+    /// 展示名称
+    fn name(&self) -> &str;
+
+    /// 获取指定页的修改器列表
+    async fn list(&self, page: u32) -> AppResult<PaginatedResponse<Trainer>>;
+
+    /// 列表是否按更新时间倒序排列，是时增量同步遇到旧条目即可停止
+    fn sorted_by_date(&self) -> bool {
+        false
+    }
+
+    /// 目录同步时获取指定页，需要跳过页面缓存的来源可以覆盖
+    async fn sync_list(&self, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+        self.list(page).await
+    }
+
+    /// 搜索修改器
+    async fn search(&self, query: &str, page: u32) -> AppResult<PaginatedResponse<Trainer>>;
+
+    /// 获取修改器详情
+    async fn detail(&self, id: &str) -> AppResult<Trainer>;
+
+    /// 解析修改器的实际下载地址
+    async fn resolve_download(&self, trainer: &Trainer) -> AppResult<String>;
+}
+
+/// 来源信息（供前端展示）
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// 获取所有可用来源，FLiNG 始终排在第一位
+pub fn all_sources() -> AppResult<Vec<Arc<dyn TrainerSource>>> {
+    let app_settings = settings::load_settings()?;
+
+    let mut sources: Vec<Arc<dyn TrainerSource>> = vec![Arc::new(fling::FlingSource::new(&app_settings.fling_base_url))];
+    for config in app_settings.custom_sources {
+        if config.id == DEFAULT_SOURCE || sources.iter().any(|s| s.id() == config.id) {
+            warn!("忽略重复的修改器来源: {}", config.id);
+            continue;
+        }
+        sources.push(Arc::new(json_index::JsonIndexSource::new(config)));
+    }
+
+    Ok(sources)
+}
+
+/// 按标识获取来源，未指定时使用默认来源
+pub fn get_source(id: Option<&str>) -> AppResult<Arc<dyn TrainerSource>> {
+    let id = id.unwrap_or(DEFAULT_SOURCE);
+    all_sources()?
+        .into_iter()
+        .find(|source| source.id() == id)
+        .ok_or_else(|| AppError::NotFoundError(format!("修改器来源 {}", id)))
+}
+
+/// 列出所有来源
+pub fn list_sources() -> AppResult<Vec<SourceInfo>> {
+    Ok(all_sources()?
+        .iter()
+        .map(|source| SourceInfo {
+            id: source.id().to_string(),
+            name: source.name().to_string(),
+        })
+        .collect())
+}
+
+/// 在所有来源中搜索并合并结果，单个来源失败不影响其他来源
+pub async fn search_all(query: &str, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
+    let sources = all_sources()?;
+    let results = join_all(sources.iter().map(|source| source.search(query, page))).await;
+
+    let mut merged = PaginatedResponse {
+        trainers: Vec::new(),
+        total: 0,
+        total_pages: 0,
+        current_page: page,
+        has_next: false,
+        stale: false,
+    };
+    let mut last_error = None;
+    let mut succeeded = false;
+
+    for (source, result) in sources.iter().zip(results) {
+        match result {
+            Ok(response) => {
+                succeeded = true;
+                merged.trainers.extend(response.trainers);
+                merged.total += response.total;
+                merged.total_pages = merged.total_pages.max(response.total_pages);
+                merged.has_next |= response.has_next;
+                merged.stale |= response.stale;
+            }
+            Err(e) => {
+                warn!("来源 {} 搜索失败: {}", source.id(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match (succeeded, last_error) {
+        (false, Some(e)) => Err(e),
+        _ => Ok(merged),
+    }
+}
@@ -2,12 +2,16 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use chrono::Utc;
-use rusqlite::{params, Connection};
+use chrono::{Local, Utc};
+use log::warn;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::models::trainer::{InstalledTrainer, Trainer};
+use crate::models::alias::GameAlias;
+use crate::models::revision::TrainerRevision;
+use crate::models::trainer::{InstalledTrainer, Trainer, TrainerDate};
 use crate::utils::path::get_app_dir;
 
 // 存储键名常量（用于前后端约定）
@@ -18,6 +22,7 @@ pub const SEARCH_RESULTS_PREFIX: &str = "searchResults_";
 
 // 缓存配置
 const CACHE_EXPIRATION_TIME: i64 = 1000 * 60 * 15; // 15分钟
+const HTTP_CACHE_RETENTION_TIME: i64 = 1000 * 60 * 60 * 24 * 30; // 30天
 const DB_FILE_NAME: &str = "app.db";
 
 // 缓存项结构（兼容 localStorage 迁移数据）
@@ -56,50 +61,7 @@ pub async fn init_db() -> Result<()> {
     let path_clone = db_path.clone();
     tauri::async_runtime::spawn_blocking(move || {
         let conn = Connection::open(path_clone)?;
-        conn.execute_batch(
-            "
-            PRAGMA journal_mode=WAL;
-            PRAGMA synchronous=NORMAL;
-            CREATE TABLE IF NOT EXISTS installed_trainers (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                version TEXT NOT NULL,
-                game_version TEXT NOT NULL,
-                download_url TEXT NOT NULL,
-                description TEXT NOT NULL,
-                thumbnail TEXT NOT NULL,
-                download_count INTEGER NOT NULL,
-                last_update TEXT NOT NULL,
-                installed_path TEXT NOT NULL,
-                install_time TEXT NOT NULL,
-                last_launch_time TEXT
-            );
-            CREATE TABLE IF NOT EXISTS downloaded_trainers (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                version TEXT NOT NULL,
-                game_version TEXT NOT NULL,
-                download_url TEXT NOT NULL,
-                description TEXT NOT NULL,
-                thumbnail TEXT NOT NULL,
-                download_count INTEGER NOT NULL,
-                last_update TEXT NOT NULL
-            );
-            CREATE TABLE IF NOT EXISTS trainer_cache (
-                page INTEGER PRIMARY KEY,
-                data TEXT NOT NULL,
-                expiration INTEGER NOT NULL
-            );
-            CREATE TABLE IF NOT EXISTS search_cache (
-                query TEXT NOT NULL,
-                page INTEGER NOT NULL,
-                data TEXT NOT NULL,
-                expiration INTEGER NOT NULL,
-                PRIMARY KEY (query, page)
-            );
-            ",
-        )?;
-        Ok::<(), rusqlite::Error>(())
+        init_schema(&conn)
     })
     .await
     .context("初始化数据库失败")??;
@@ -107,6 +69,426 @@ pub async fn init_db() -> Result<()> {
     Ok(())
 }
 
+// 安装记录表，不同来源可能有相同的修改器 ID
+const INSTALLED_TRAINERS_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS installed_trainers (
+        id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        version TEXT NOT NULL,
+        game_version TEXT NOT NULL,
+        download_url TEXT NOT NULL,
+        description TEXT NOT NULL,
+        thumbnail TEXT NOT NULL,
+        download_count INTEGER NOT NULL,
+        last_update TEXT NOT NULL,
+        source TEXT NOT NULL DEFAULT 'fling',
+        options TEXT NOT NULL DEFAULT '[]',
+        installed_path TEXT NOT NULL,
+        install_time TEXT NOT NULL,
+        last_launch_time TEXT,
+        installed_sha256 TEXT,
+        PRIMARY KEY (source, id)
+    )
+";
+
+// 下载记录表，主键同安装记录表
+const DOWNLOADED_TRAINERS_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS downloaded_trainers (
+        id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        version TEXT NOT NULL,
+        game_version TEXT NOT NULL,
+        download_url TEXT NOT NULL,
+        description TEXT NOT NULL,
+        thumbnail TEXT NOT NULL,
+        download_count INTEGER NOT NULL,
+        last_update TEXT NOT NULL,
+        source TEXT NOT NULL DEFAULT 'fling',
+        options TEXT NOT NULL DEFAULT '[]',
+        downloads TEXT NOT NULL DEFAULT '[]',
+        sha256 TEXT,
+        mirrors TEXT NOT NULL DEFAULT '[]',
+        PRIMARY KEY (source, id)
+    )
+";
+
+// 建表并迁移旧版本数据库
+fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(INSTALLED_TRAINERS_TABLE, [])?;
+    conn.execute(DOWNLOADED_TRAINERS_TABLE, [])?;
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode=WAL;
+        PRAGMA synchronous=NORMAL;
+        CREATE TABLE IF NOT EXISTS trainer_cache (
+            page INTEGER PRIMARY KEY,
+            data TEXT NOT NULL,
+            expiration INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS search_cache (
+            query TEXT NOT NULL,
+            page INTEGER NOT NULL,
+            data TEXT NOT NULL,
+            expiration INTEGER NOT NULL,
+            PRIMARY KEY (query, page)
+        );
+        CREATE TABLE IF NOT EXISTS http_cache (
+            url TEXT PRIMARY KEY,
+            etag TEXT,
+            last_modified TEXT,
+            body_path TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS download_queue (
+            file_id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            save_path TEXT NOT NULL,
+            event_name TEXT NOT NULL,
+            priority INTEGER NOT NULL DEFAULT 0,
+            position INTEGER NOT NULL DEFAULT 0,
+            state TEXT NOT NULL,
+            downloaded_bytes INTEGER NOT NULL DEFAULT 0,
+            total_bytes INTEGER,
+            validator TEXT,
+            payload TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS image_cache (
+            source TEXT NOT NULL,
+            trainer_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            path TEXT,
+            size INTEGER NOT NULL DEFAULT 0,
+            content_type TEXT,
+            last_access INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (source, trainer_id)
+        );
+        CREATE TABLE IF NOT EXISTS catalog (
+            source TEXT NOT NULL,
+            id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            version TEXT NOT NULL,
+            game_version TEXT NOT NULL,
+            download_url TEXT NOT NULL,
+            description TEXT NOT NULL,
+            thumbnail TEXT NOT NULL,
+            download_count INTEGER NOT NULL,
+            last_update TEXT NOT NULL,
+            options TEXT NOT NULL DEFAULT '[]',
+            downloads TEXT NOT NULL DEFAULT '[]',
+            sha256 TEXT,
+            mirrors TEXT NOT NULL DEFAULT '[]',
+            synced_at INTEGER NOT NULL,
+            PRIMARY KEY (source, id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_catalog_last_update
+            ON catalog (last_update DESC);
+        CREATE TABLE IF NOT EXISTS catalog_sync (
+            source TEXT PRIMARY KEY,
+            last_sync_at INTEGER NOT NULL,
+            newest_update TEXT NOT NULL,
+            total_pages INTEGER NOT NULL,
+            item_count INTEGER NOT NULL
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS catalog_fts USING fts5(
+            source UNINDEXED,
+            id UNINDEXED,
+            name,
+            game_version,
+            options,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS catalog_fts_vocab
+            USING fts5vocab(catalog_fts, 'row');
+        CREATE TABLE IF NOT EXISTS trainer_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source TEXT NOT NULL,
+            trainer_id TEXT NOT NULL,
+            version TEXT NOT NULL,
+            game_version TEXT NOT NULL,
+            last_update TEXT NOT NULL,
+            options TEXT NOT NULL DEFAULT '[]',
+            recorded_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_trainer_revisions_trainer
+            ON trainer_revisions (source, trainer_id, id);
+        CREATE TABLE IF NOT EXISTS game_aliases (
+            alias_key TEXT NOT NULL,
+            alias TEXT NOT NULL,
+            source TEXT NOT NULL DEFAULT 'fling',
+            trainer_id TEXT NOT NULL,
+            language TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY (alias_key, source, trainer_id)
+        );
+        ",
+    )?;
+
+    // 旧版本数据库没有来源列，补齐后默认归属 FLiNG
+    ensure_column(conn, "installed_trainers", "source", "TEXT NOT NULL DEFAULT 'fling'")?;
+    ensure_column(conn, "downloaded_trainers", "source", "TEXT NOT NULL DEFAULT 'fling'")?;
+    ensure_column(conn, "installed_trainers", "options", "TEXT NOT NULL DEFAULT '[]'")?;
+    ensure_column(conn, "downloaded_trainers", "options", "TEXT NOT NULL DEFAULT '[]'")?;
+    ensure_column(conn, "installed_trainers", "installed_sha256", "TEXT")?;
+    ensure_column(conn, "downloaded_trainers", "downloads", "TEXT NOT NULL DEFAULT '[]'")?;
+    ensure_column(conn, "downloaded_trainers", "sha256", "TEXT")?;
+    ensure_column(conn, "downloaded_trainers", "mirrors", "TEXT NOT NULL DEFAULT '[]'")?;
+    rekey_by_source(conn, "installed_trainers", INSTALLED_TRAINERS_TABLE)?;
+    rekey_by_source(conn, "downloaded_trainers", DOWNLOADED_TRAINERS_TABLE)?;
+    migrate_detail_ids(conn)?;
+
+    // 旧数据中的日期为网站原文，统一转换为 ISO 格式
+    normalize_dates(conn, "installed_trainers")?;
+    normalize_dates(conn, "downloaded_trainers")?;
+
+    // 全文索引与目录条目数不一致时重建
+    let in_sync: bool = conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM catalog) = (SELECT COUNT(*) FROM catalog_fts)",
+        [],
+        |row| row.get(0),
+    )?;
+    if !in_sync {
+        conn.execute("DELETE FROM catalog_fts", [])?;
+        conn.execute(CATALOG_FTS_INSERT, [])?;
+    }
+    Ok(())
+}
+
+// 表的列名和该列在主键中的位置（不在主键中为 0）
+fn table_columns(conn: &Connection, table: &str) -> rusqlite::Result<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, i64>(5)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(columns)
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    Ok(table_columns(conn, table)?
+        .iter()
+        .any(|(name, _)| name == column))
+}
+
+// 旧版本的表只以修改器 ID 为主键，不同来源的同名 ID 会互相覆盖；按新结构重建并复制原有记录
+fn rekey_by_source(conn: &Connection, table: &str, schema: &str) -> rusqlite::Result<()> {
+    let keyed_by_source = table_columns(conn, table)?
+        .iter()
+        .any(|(name, pk)| name == "source" && *pk > 0);
+    if keyed_by_source {
+        return Ok(());
+    }
+
+    let legacy = format!("{}_legacy", table);
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(&format!("ALTER TABLE {} RENAME TO {}", table, legacy), [])?;
+    tx.execute(schema, [])?;
+    let columns = table_columns(&tx, table)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    tx.execute(
+        &format!(
+            "INSERT INTO {} ({columns}) SELECT {columns} FROM {}",
+            table,
+            legacy,
+            columns = columns
+        ),
+        [],
+    )?;
+    tx.execute(&format!("DROP TABLE {}", legacy), [])?;
+    tx.commit()
+}
+
+// 为旧版本数据库补充新增的列
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !has_column(conn, table, column)? {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// 旧版本详情页的 ID 去掉了 "-trainer" 后缀，与列表页不一致；统一为完整的 URL 路径段，
+// 同时重命名安装目录并更新 trainer.json。通过 user_version 保证只执行一次
+fn migrate_detail_ids(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version >= 1 {
+        return Ok(());
+    }
+
+    let installed = {
+        let mut stmt = conn.prepare(
+            "
+            SELECT id, name, installed_path FROM installed_trainers
+            WHERE source = 'fling' AND id NOT LIKE '%-trainer'
+            ",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    for (old_id, name, installed_path) in installed {
+        let new_id = format!("{}-trainer", old_id);
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM installed_trainers WHERE source = 'fling' AND id = ?1)",
+            params![new_id],
+            |row| row.get(0),
+        )?;
+        if taken {
+            continue;
+        }
+        let new_path = migrate_install_dir(&installed_path, &name, &old_id, &new_id);
+        tx.execute(
+            "UPDATE installed_trainers SET id = ?1, installed_path = ?2 WHERE source = 'fling' AND id = ?3",
+            params![new_id, new_path, old_id],
+        )?;
+    }
+
+    // 同一修改器已有新 ID 的下载记录时保留新记录
+    tx.execute(
+        "
+        UPDATE OR IGNORE downloaded_trainers SET id = id || '-trainer'
+        WHERE source = 'fling' AND id NOT LIKE '%-trainer'
+        ",
+        [],
+    )?;
+    tx.execute(
+        "
+        DELETE FROM downloaded_trainers
+        WHERE source = 'fling' AND id NOT LIKE '%-trainer'
+        ",
+        [],
+    )?;
+    tx.execute(
+        "
+        UPDATE trainer_revisions SET trainer_id = trainer_id || '-trainer'
+        WHERE source = 'fling' AND trainer_id NOT LIKE '%-trainer'
+        ",
+        [],
+    )?;
+    tx.execute(
+        "
+        UPDATE OR IGNORE game_aliases SET trainer_id = trainer_id || '-trainer'
+        WHERE source = 'fling' AND trainer_id NOT LIKE '%-trainer'
+        ",
+        [],
+    )?;
+    tx.execute("PRAGMA user_version = 1", [])?;
+    tx.commit()
+}
+
+// 重命名以旧 ID 结尾的安装目录并更新其中的 trainer.json，返回新的安装路径；
+// 目录不存在或无法重命名时保留原路径
+fn migrate_install_dir(installed_path: &str, name: &str, old_id: &str, new_id: &str) -> String {
+    let old_dir = PathBuf::from(installed_path);
+    let suffix = format!("_{}", old_id);
+    let renamed = old_dir
+        .file_name()
+        .and_then(|dir_name| dir_name.to_str())
+        .and_then(|dir_name| dir_name.strip_suffix(&suffix))
+        .map(|prefix| old_dir.with_file_name(format!("{}_{}", prefix, new_id)))
+        .filter(|new_dir| old_dir.is_dir() && !new_dir.exists())
+        .filter(|new_dir| std::fs::rename(&old_dir, new_dir).is_ok());
+    let dir = renamed.unwrap_or(old_dir);
+
+    let info_path = dir.join("trainer.json");
+    let info = std::fs::read_to_string(&info_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    if let Some(mut info) = info {
+        info["trainer"]["id"] = Value::from(new_id);
+        info["install_path"] = Value::from(dir.to_string_lossy().to_string());
+        match serde_json::to_string_pretty(&info) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(&info_path, content) {
+                    warn!("更新安装信息失败 {}: {}", name, e);
+                }
+            }
+            Err(e) => warn!("更新安装信息失败 {}: {}", name, e),
+        }
+    }
+
+    dir.to_string_lossy().to_string()
+}
+
+// 将 last_update 列中可识别的日期改写为 ISO 格式
+fn normalize_dates(conn: &Connection, table: &str) -> rusqlite::Result<()> {
+    let rows = {
+        let mut stmt = conn.prepare(&format!("SELECT rowid, last_update FROM {}", table))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+
+    for (id, raw) in rows {
+        let normalized = TrainerDate::parse(&raw).to_string();
+        if normalized != raw {
+            conn.execute(
+                &format!("UPDATE {} SET last_update = ?1 WHERE rowid = ?2", table),
+                params![normalized, id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// 目录查询使用的列，顺序与 catalog_row_to_trainer 对应
+const CATALOG_COLUMNS: &str = "
+    c.id, c.name, c.version, c.game_version, c.download_url,
+    c.description, c.thumbnail, c.download_count, c.last_update,
+    c.source, c.options, c.downloads, c.sha256, c.mirrors
+";
+
+// 从目录表写入全文索引，功能列表只取说明文本
+const CATALOG_FTS_INSERT: &str = "
+    INSERT INTO catalog_fts (source, id, name, game_version, options)
+    SELECT source, id, name, game_version,
+           COALESCE((SELECT group_concat(json_extract(value, '$.label'), ' ')
+                     FROM json_each(catalog.options)), '')
+    FROM catalog
+";
+
+fn catalog_row_to_trainer(row: &rusqlite::Row) -> rusqlite::Result<Trainer> {
+    Ok(Trainer {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        version: row.get(2)?,
+        game_version: row.get(3)?,
+        download_url: row.get(4)?,
+        description: row.get(5)?,
+        thumbnail: row.get(6)?,
+        download_count: row.get(7)?,
+        last_update: TrainerDate::parse(&row.get::<_, String>(8)?),
+        source: row.get(9)?,
+        options: list_from_json(row.get(10)?),
+        downloads: list_from_json(row.get(11)?),
+        mirrors: list_from_json(row.get(13)?),
+        sha256: row.get(12)?,
+    })
+}
+
+// 功能列表、下载项等列表以 JSON 文本存储
+fn list_to_json<T: Serialize>(items: &[T]) -> String {
+    serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn list_from_json<T: DeserializeOwned>(text: String) -> Vec<T> {
+    serde_json::from_str(&text).unwrap_or_default()
+}
+
 pub async fn save_installed_trainers(trainers: Vec<InstalledTrainer>) -> Result<()> {
     with_conn(move |conn| {
         let tx = conn.transaction()?;
@@ -118,8 +500,8 @@ pub async fn save_installed_trainers(trainers: Vec<InstalledTrainer>) -> Result<
                 INSERT INTO installed_trainers (
                     id, name, version, game_version, download_url,
                     description, thumbnail, download_count, last_update,
-                    installed_path, install_time, last_launch_time
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                    source, options, installed_path, install_time, last_launch_time, installed_sha256
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
                 ",
             )?;
 
@@ -133,10 +515,13 @@ pub async fn save_installed_trainers(trainers: Vec<InstalledTrainer>) -> Result<
                     t.description,
                     t.thumbnail,
                     t.download_count,
-                    t.last_update,
+                    t.last_update.to_string(),
+                    t.source,
+                    list_to_json(&t.options),
                     t.installed_path,
                     t.install_time,
                     t.last_launch_time,
+                    t.installed_sha256,
                 ])?;
             }
         }
@@ -153,7 +538,7 @@ pub async fn get_installed_trainers() -> Result<Vec<InstalledTrainer>> {
             "
             SELECT id, name, version, game_version, download_url,
                    description, thumbnail, download_count, last_update,
-                   installed_path, install_time, last_launch_time
+                   source, options, installed_path, install_time, last_launch_time, installed_sha256
             FROM installed_trainers
             ",
         )?;
@@ -168,10 +553,13 @@ pub async fn get_installed_trainers() -> Result<Vec<InstalledTrainer>> {
                 description: row.get(5)?,
                 thumbnail: row.get(6)?,
                 download_count: row.get(7)?,
-                last_update: row.get(8)?,
-                installed_path: row.get(9)?,
-                install_time: row.get(10)?,
-                last_launch_time: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+                last_update: TrainerDate::parse(&row.get::<_, String>(8)?),
+                source: row.get(9)?,
+                options: list_from_json(row.get(10)?),
+                installed_path: row.get(11)?,
+                install_time: row.get(12)?,
+                last_launch_time: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                installed_sha256: row.get(14)?,
             })
         })?;
 
@@ -194,8 +582,9 @@ pub async fn save_downloaded_trainers(trainers: Vec<Trainer>) -> Result<()> {
                 "
                 INSERT INTO downloaded_trainers (
                     id, name, version, game_version, download_url,
-                    description, thumbnail, download_count, last_update
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                    description, thumbnail, download_count, last_update, source, options, downloads,
+                    sha256, mirrors
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
                 ",
             )?;
 
@@ -209,7 +598,12 @@ pub async fn save_downloaded_trainers(trainers: Vec<Trainer>) -> Result<()> {
                     t.description,
                     t.thumbnail,
                     t.download_count,
-                    t.last_update
+                    t.last_update.to_string(),
+                    t.source,
+                    list_to_json(&t.options),
+                    list_to_json(&t.downloads),
+                    t.sha256,
+                    list_to_json(&t.mirrors)
                 ])?;
             }
         }
@@ -225,7 +619,8 @@ pub async fn get_downloaded_trainers() -> Result<Vec<Trainer>> {
         let mut stmt = conn.prepare(
             "
             SELECT id, name, version, game_version, download_url,
-                   description, thumbnail, download_count, last_update
+                   description, thumbnail, download_count, last_update, source, options, downloads,
+                   sha256, mirrors
             FROM downloaded_trainers
             ",
         )?;
@@ -240,7 +635,12 @@ pub async fn get_downloaded_trainers() -> Result<Vec<Trainer>> {
                 description: row.get(5)?,
                 thumbnail: row.get(6)?,
                 download_count: row.get(7)?,
-                last_update: row.get(8)?,
+                last_update: TrainerDate::parse(&row.get::<_, String>(8)?),
+                source: row.get(9)?,
+                options: list_from_json(row.get(10)?),
+                downloads: list_from_json(row.get(11)?),
+                mirrors: list_from_json(row.get(13)?),
+                sha256: row.get(12)?,
             })
         })?;
 
@@ -259,9 +659,10 @@ pub async fn upsert_downloaded_trainer(trainer: Trainer) -> Result<()> {
             "
             INSERT INTO downloaded_trainers (
                 id, name, version, game_version, download_url,
-                description, thumbnail, download_count, last_update
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-            ON CONFLICT(id) DO UPDATE SET
+                description, thumbnail, download_count, last_update, source, options, downloads,
+                sha256, mirrors
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT(source, id) DO UPDATE SET
                 name = excluded.name,
                 version = excluded.version,
                 game_version = excluded.game_version,
@@ -269,7 +670,11 @@ pub async fn upsert_downloaded_trainer(trainer: Trainer) -> Result<()> {
                 description = excluded.description,
                 thumbnail = excluded.thumbnail,
                 download_count = excluded.download_count,
-                last_update = excluded.last_update
+                last_update = excluded.last_update,
+                options = excluded.options,
+                downloads = excluded.downloads,
+                sha256 = excluded.sha256,
+                mirrors = excluded.mirrors
             ",
             params![
                 trainer.id,
@@ -280,7 +685,12 @@ pub async fn upsert_downloaded_trainer(trainer: Trainer) -> Result<()> {
                 trainer.description,
                 trainer.thumbnail,
                 trainer.download_count,
-                trainer.last_update,
+                trainer.last_update.to_string(),
+                trainer.source,
+                list_to_json(&trainer.options),
+                list_to_json(&trainer.downloads),
+                trainer.sha256,
+                list_to_json(&trainer.mirrors),
             ],
         )?;
         Ok(())
@@ -288,10 +698,14 @@ pub async fn upsert_downloaded_trainer(trainer: Trainer) -> Result<()> {
     .await
 }
 
-pub async fn remove_downloaded_trainer(trainer_id: &str) -> Result<()> {
+pub async fn remove_downloaded_trainer(source: &str, trainer_id: &str) -> Result<()> {
+    let source = source.to_string();
     let id = trainer_id.to_string();
     with_conn(move |conn| {
-        conn.execute("DELETE FROM downloaded_trainers WHERE id = ?1", params![id])?;
+        conn.execute(
+            "DELETE FROM downloaded_trainers WHERE source = ?1 AND id = ?2",
+            params![source, id],
+        )?;
         Ok(())
     })
     .await
@@ -304,9 +718,9 @@ pub async fn upsert_installed_trainer(trainer: InstalledTrainer) -> Result<()> {
             INSERT INTO installed_trainers (
                 id, name, version, game_version, download_url,
                 description, thumbnail, download_count, last_update,
-                installed_path, install_time, last_launch_time
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
-            ON CONFLICT(id) DO UPDATE SET
+                source, options, installed_path, install_time, last_launch_time, installed_sha256
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            ON CONFLICT(source, id) DO UPDATE SET
                 name = excluded.name,
                 version = excluded.version,
                 game_version = excluded.game_version,
@@ -315,9 +729,11 @@ pub async fn upsert_installed_trainer(trainer: InstalledTrainer) -> Result<()> {
                 thumbnail = excluded.thumbnail,
                 download_count = excluded.download_count,
                 last_update = excluded.last_update,
+                options = excluded.options,
                 installed_path = excluded.installed_path,
                 install_time = excluded.install_time,
-                last_launch_time = excluded.last_launch_time
+                last_launch_time = excluded.last_launch_time,
+                installed_sha256 = excluded.installed_sha256
             ",
             params![
                 trainer.id,
@@ -328,10 +744,13 @@ pub async fn upsert_installed_trainer(trainer: InstalledTrainer) -> Result<()> {
                 trainer.description,
                 trainer.thumbnail,
                 trainer.download_count,
-                trainer.last_update,
+                trainer.last_update.to_string(),
+                trainer.source,
+                list_to_json(&trainer.options),
                 trainer.installed_path,
                 trainer.install_time,
-                trainer.last_launch_time
+                trainer.last_launch_time,
+                trainer.installed_sha256
             ],
         )?;
         Ok(())
@@ -339,18 +758,19 @@ pub async fn upsert_installed_trainer(trainer: InstalledTrainer) -> Result<()> {
     .await
 }
 
-pub async fn get_installed_trainer_by_id(id: &str) -> Result<Option<InstalledTrainer>> {
+pub async fn get_installed_trainer_by_id(source: &str, id: &str) -> Result<Option<InstalledTrainer>> {
+    let source = source.to_string();
     let trainer_id = id.to_string();
     with_conn(move |conn| {
         let mut stmt = conn.prepare(
             "
             SELECT id, name, version, game_version, download_url,
                    description, thumbnail, download_count, last_update,
-                   installed_path, install_time, last_launch_time
-            FROM installed_trainers WHERE id = ?1
+                   source, options, installed_path, install_time, last_launch_time, installed_sha256
+            FROM installed_trainers WHERE source = ?1 AND id = ?2
             ",
         )?;
-        let mut rows = stmt.query(params![trainer_id])?;
+        let mut rows = stmt.query(params![source, trainer_id])?;
         if let Some(row) = rows.next()? {
             let trainer = InstalledTrainer {
                 id: row.get(0)?,
@@ -361,10 +781,13 @@ pub async fn get_installed_trainer_by_id(id: &str) -> Result<Option<InstalledTra
                 description: row.get(5)?,
                 thumbnail: row.get(6)?,
                 download_count: row.get(7)?,
-                last_update: row.get(8)?,
-                installed_path: row.get(9)?,
-                install_time: row.get(10)?,
-                last_launch_time: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+                last_update: TrainerDate::parse(&row.get::<_, String>(8)?),
+                source: row.get(9)?,
+                options: list_from_json(row.get(10)?),
+                installed_path: row.get(11)?,
+                install_time: row.get(12)?,
+                last_launch_time: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                installed_sha256: row.get(14)?,
             };
             Ok(Some(trainer))
         } else {
@@ -374,22 +797,27 @@ pub async fn get_installed_trainer_by_id(id: &str) -> Result<Option<InstalledTra
     .await
 }
 
-pub async fn remove_installed_trainer(id: &str) -> Result<()> {
+pub async fn remove_installed_trainer(source: &str, id: &str) -> Result<()> {
+    let source = source.to_string();
     let trainer_id = id.to_string();
     with_conn(move |conn| {
-        conn.execute("DELETE FROM installed_trainers WHERE id = ?1", params![trainer_id])?;
+        conn.execute(
+            "DELETE FROM installed_trainers WHERE source = ?1 AND id = ?2",
+            params![source, trainer_id],
+        )?;
         Ok(())
     })
     .await
 }
 
-pub async fn update_last_launch_time(id: &str, timestamp: &str) -> Result<()> {
+pub async fn update_last_launch_time(source: &str, id: &str, timestamp: &str) -> Result<()> {
+    let source = source.to_string();
     let trainer_id = id.to_string();
     let ts = timestamp.to_string();
     with_conn(move |conn| {
         conn.execute(
-            "UPDATE installed_trainers SET last_launch_time = ?1 WHERE id = ?2",
-            params![ts, trainer_id],
+            "UPDATE installed_trainers SET last_launch_time = ?1 WHERE source = ?2 AND id = ?3",
+            params![ts, source, trainer_id],
         )?;
         Ok(())
     })
@@ -501,11 +929,884 @@ pub async fn clean_expired_cache() -> Result<()> {
             "DELETE FROM search_cache WHERE expiration < ?1",
             params![now],
         )?;
+
+        // 长期未访问的页面缓存连同磁盘文件一起删除
+        remove_http_cache_before(conn, now - HTTP_CACHE_RETENTION_TIME)?;
+        Ok(())
+    })
+    .await
+}
+
+// 删除指定时间之前确认过的页面缓存及其正文文件
+fn remove_http_cache_before(conn: &Connection, threshold: i64) -> rusqlite::Result<()> {
+    let paths = {
+        let mut stmt = conn.prepare("SELECT body_path FROM http_cache WHERE fetched_at < ?1")?;
+        let paths = stmt
+            .query_map(params![threshold], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        paths
+    };
+    conn.execute("DELETE FROM http_cache WHERE fetched_at < ?1", params![threshold])?;
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// 页面缓存记录，正文保存在磁盘文件中
+#[derive(Debug, Clone)]
+pub struct HttpCacheEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body_path: String,
+    /// 最近一次确认内容有效的时间（毫秒时间戳）
+    pub fetched_at: i64,
+}
+
+pub async fn get_http_cache_entry(url: &str) -> Result<Option<HttpCacheEntry>> {
+    let url = url.to_string();
+    with_conn(move |conn| {
+        Ok(conn
+            .query_row(
+                "
+                SELECT url, etag, last_modified, body_path, fetched_at
+                FROM http_cache WHERE url = ?1
+                ",
+                params![url],
+                |row| {
+                    Ok(HttpCacheEntry {
+                        url: row.get(0)?,
+                        etag: row.get(1)?,
+                        last_modified: row.get(2)?,
+                        body_path: row.get(3)?,
+                        fetched_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?)
+    })
+    .await
+}
+
+pub async fn save_http_cache_entry(entry: HttpCacheEntry) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "
+            INSERT INTO http_cache (url, etag, last_modified, body_path, fetched_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(url) DO UPDATE SET
+                etag = excluded.etag,
+                last_modified = excluded.last_modified,
+                body_path = excluded.body_path,
+                fetched_at = excluded.fetched_at
+            ",
+            params![
+                entry.url,
+                entry.etag,
+                entry.last_modified,
+                entry.body_path,
+                entry.fetched_at,
+            ],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+// 服务器返回 304 时只刷新确认时间
+pub async fn touch_http_cache_entry(url: &str, fetched_at: i64) -> Result<()> {
+    let url = url.to_string();
+    with_conn(move |conn| {
+        conn.execute(
+            "UPDATE http_cache SET fetched_at = ?1 WHERE url = ?2",
+            params![fetched_at, url],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// 目录同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSyncState {
+    pub source: String,
+    /// 上次同步完成时间（毫秒时间戳）
+    pub last_sync_at: i64,
+    /// 已同步条目中最新的更新日期，增量同步以此为界
+    pub newest_update: TrainerDate,
+    pub total_pages: u32,
+    pub item_count: u32,
+}
+
+// 写入目录条目，列表页没有功能列表时保留已有数据
+pub async fn upsert_catalog_trainers(trainers: Vec<Trainer>) -> Result<usize> {
+    let now = Utc::now().timestamp_millis();
+    with_conn(move |conn| {
+        let tx = conn.transaction()?;
+        let count = trainers.len();
+        upsert_catalog_rows(&tx, trainers, now)?;
+        tx.commit()?;
+        Ok(count)
+    })
+    .await
+}
+
+// 写入目录条目并同步全文索引，已有条目中详情页才有的字段不会被列表页数据清空
+fn upsert_catalog_rows(conn: &Connection, trainers: Vec<Trainer>, now: i64) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(
+        "
+        INSERT INTO catalog (
+            source, id, name, version, game_version, download_url,
+            description, thumbnail, download_count, last_update,
+            options, downloads, sha256, mirrors, synced_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+        ON CONFLICT(source, id) DO UPDATE SET
+            name = excluded.name,
+            version = excluded.version,
+            game_version = excluded.game_version,
+            download_url = CASE WHEN excluded.download_url = ''
+                THEN catalog.download_url ELSE excluded.download_url END,
+            description = CASE WHEN excluded.description = ''
+                THEN catalog.description ELSE excluded.description END,
+            thumbnail = excluded.thumbnail,
+            download_count = MAX(catalog.download_count, excluded.download_count),
+            last_update = excluded.last_update,
+            options = CASE WHEN excluded.options = '[]'
+                THEN catalog.options ELSE excluded.options END,
+            downloads = CASE WHEN excluded.downloads = '[]'
+                THEN catalog.downloads ELSE excluded.downloads END,
+            sha256 = CASE WHEN excluded.download_url = ''
+                THEN catalog.sha256 ELSE excluded.sha256 END,
+            mirrors = CASE WHEN excluded.download_url = ''
+                THEN catalog.mirrors ELSE excluded.mirrors END,
+            synced_at = excluded.synced_at
+        ",
+    )?;
+    let mut delete_fts =
+        conn.prepare("DELETE FROM catalog_fts WHERE source = ?1 AND id = ?2")?;
+    let mut insert_fts = conn.prepare(&format!(
+        "{} WHERE source = ?1 AND id = ?2",
+        CATALOG_FTS_INSERT
+    ))?;
+
+    for t in trainers {
+        stmt.execute(params![
+            t.source,
+            t.id,
+            t.name,
+            t.version,
+            t.game_version,
+            t.download_url,
+            t.description,
+            t.thumbnail,
+            t.download_count,
+            t.last_update.to_string(),
+            list_to_json(&t.options),
+            list_to_json(&t.downloads),
+            t.sha256,
+            list_to_json(&t.mirrors),
+            now,
+        ])?;
+        delete_fts.execute(params![t.source, t.id])?;
+        insert_fts.execute(params![t.source, t.id])?;
+    }
+    Ok(())
+}
+
+// 按更新日期倒序分页读取目录，返回当前页数据和总条数
+pub async fn get_catalog_trainers(
+    source: Option<String>,
+    page: u32,
+    page_size: u32,
+) -> Result<(Vec<Trainer>, u32)> {
+    let offset = page.saturating_sub(1) * page_size;
+    with_conn(move |conn| {
+        let total: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM catalog WHERE ?1 IS NULL OR source = ?1",
+            params![source],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "
+            SELECT {}
+            FROM catalog c
+            WHERE ?1 IS NULL OR c.source = ?1
+            ORDER BY c.last_update DESC, c.name ASC
+            LIMIT ?2 OFFSET ?3
+            ",
+            CATALOG_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(
+            params![source, page_size, offset],
+            catalog_row_to_trainer,
+        )?;
+
+        let mut result = Vec::new();
+        for trainer in rows {
+            result.push(trainer?);
+        }
+        Ok((result, total))
+    })
+    .await
+}
+
+pub async fn get_catalog_trainer(source: String, id: String) -> Result<Option<Trainer>> {
+    with_conn(move |conn| {
+        Ok(conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM catalog c WHERE c.source = ?1 AND c.id = ?2",
+                    CATALOG_COLUMNS
+                ),
+                params![source, id],
+                catalog_row_to_trainer,
+            )
+            .optional()?)
+    })
+    .await
+}
+
+pub async fn get_catalog_sync_state(source: &str) -> Result<Option<CatalogSyncState>> {
+    let source = source.to_string();
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare(
+            "
+            SELECT source, last_sync_at, newest_update, total_pages, item_count
+            FROM catalog_sync WHERE source = ?1
+            ",
+        )?;
+        let mut rows = stmt.query(params![source])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(CatalogSyncState {
+                source: row.get(0)?,
+                last_sync_at: row.get(1)?,
+                newest_update: TrainerDate::parse(&row.get::<_, String>(2)?),
+                total_pages: row.get(3)?,
+                item_count: row.get(4)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    })
+    .await
+}
+
+pub async fn save_catalog_sync_state(state: CatalogSyncState) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "
+            INSERT INTO catalog_sync (source, last_sync_at, newest_update, total_pages, item_count)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(source) DO UPDATE SET
+                last_sync_at = excluded.last_sync_at,
+                newest_update = excluded.newest_update,
+                total_pages = excluded.total_pages,
+                item_count = excluded.item_count
+            ",
+            params![
+                state.source,
+                state.last_sync_at,
+                state.newest_update.to_string(),
+                state.total_pages,
+                state.item_count,
+            ],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// 全文搜索目录，`match_expr` 为 FTS5 查询表达式，按相关度排序
+pub async fn search_catalog(
+    source: Option<String>,
+    match_expr: String,
+    page: u32,
+    page_size: u32,
+) -> Result<(Vec<Trainer>, u32)> {
+    let offset = page.saturating_sub(1) * page_size;
+    with_conn(move |conn| {
+        let total: u32 = conn.query_row(
+            "
+            SELECT COUNT(*) FROM catalog_fts
+            WHERE catalog_fts MATCH ?1 AND (?2 IS NULL OR source = ?2)
+            ",
+            params![match_expr, source],
+            |row| row.get(0),
+        )?;
+
+        // 名称权重最高，其次是游戏版本和功能说明
+        let mut stmt = conn.prepare(&format!(
+            "
+            SELECT {}
+            FROM catalog_fts f
+            JOIN catalog c ON c.source = f.source AND c.id = f.id
+            WHERE catalog_fts MATCH ?1 AND (?2 IS NULL OR f.source = ?2)
+            ORDER BY bm25(catalog_fts, 0.0, 0.0, 10.0, 2.0, 1.0), c.last_update DESC
+            LIMIT ?3 OFFSET ?4
+            ",
+            CATALOG_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(
+            params![match_expr, source, page_size, offset],
+            catalog_row_to_trainer,
+        )?;
+
+        let mut result = Vec::new();
+        for trainer in rows {
+            result.push(trainer?);
+        }
+        Ok((result, total))
+    })
+    .await
+}
+
+// 全文索引词表（词 + 出现的条目数），用于纠正拼写错误
+pub async fn get_catalog_terms() -> Result<Vec<(String, u32)>> {
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare("SELECT term, doc FROM catalog_fts_vocab")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut result = Vec::new();
+        for term in rows {
+            result.push(term?);
+        }
+        Ok(result)
+    })
+    .await
+}
+
+// 统计目录条目数，未指定来源时统计全部
+pub async fn count_catalog(source: Option<&str>) -> Result<u32> {
+    let source = source.map(str::to_string);
+    with_conn(move |conn| Ok(count_catalog_rows(conn, source.as_deref())?)).await
+}
+
+fn count_catalog_rows(conn: &Connection, source: Option<&str>) -> rusqlite::Result<u32> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM catalog WHERE ?1 IS NULL OR source = ?1",
+        params![source],
+        |row| row.get(0),
+    )
+}
+
+/// 记录修改器的抓取版本，与最近一次记录相同时跳过，返回是否新增了记录
+pub async fn record_trainer_revision(trainer: Trainer) -> Result<bool> {
+    let recorded_at = Local::now().to_rfc3339();
+    with_conn(move |conn| {
+        let options = list_to_json(&trainer.options);
+        let last_update = trainer.last_update.to_string();
+
+        let latest = conn
+            .query_row(
+                "
+                SELECT version, game_version, last_update, options FROM trainer_revisions
+                WHERE source = ?1 AND trainer_id = ?2
+                ORDER BY id DESC LIMIT 1
+                ",
+                params![trainer.source, trainer.id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+        let current = (
+            trainer.version.clone(),
+            trainer.game_version.clone(),
+            last_update.clone(),
+            options.clone(),
+        );
+        if latest.as_ref() == Some(&current) {
+            return Ok(false);
+        }
+
+        conn.execute(
+            "
+            INSERT INTO trainer_revisions (
+                source, trainer_id, version, game_version, last_update, options, recorded_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ",
+            params![
+                trainer.source,
+                trainer.id,
+                trainer.version,
+                trainer.game_version,
+                last_update,
+                options,
+                recorded_at,
+            ],
+        )?;
+        Ok(true)
+    })
+    .await
+}
+
+fn revision_from_row(row: &rusqlite::Row) -> rusqlite::Result<TrainerRevision> {
+    Ok(TrainerRevision {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        trainer_id: row.get(2)?,
+        version: row.get(3)?,
+        game_version: row.get(4)?,
+        last_update: TrainerDate::parse(&row.get::<_, String>(5)?),
+        options: list_from_json(row.get(6)?),
+        recorded_at: row.get(7)?,
+    })
+}
+
+// 按记录顺序获取修改器的全部版本
+pub async fn get_trainer_revisions(source: String, trainer_id: String) -> Result<Vec<TrainerRevision>> {
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare(
+            "
+            SELECT id, source, trainer_id, version, game_version, last_update, options, recorded_at
+            FROM trainer_revisions
+            WHERE source = ?1 AND trainer_id = ?2
+            ORDER BY id ASC
+            ",
+        )?;
+
+        let rows = stmt.query_map(params![source, trainer_id], revision_from_row)?;
+
+        let mut result = Vec::new();
+        for revision in rows {
+            result.push(revision?);
+        }
+        Ok(result)
+    })
+    .await
+}
+
+/// 下载队列记录，用于重启后恢复未完成的下载
+#[derive(Debug, Clone)]
+pub struct DownloadQueueRecord {
+    pub file_id: String,
+    pub url: String,
+    /// 未完成文件的路径
+    pub save_path: String,
+    pub event_name: String,
+    pub priority: i32,
+    pub position: i64,
+    pub state: String,
+    /// 已写入文件的字节数
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    /// 续传校验值（ETag 或 Last-Modified）
+    pub validator: Option<String>,
+    /// 调用方附带的数据（JSON）
+    pub payload: String,
+}
+
+pub async fn save_download_record(record: DownloadQueueRecord) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "
+            INSERT INTO download_queue (
+                file_id, url, save_path, event_name, priority, position, state,
+                downloaded_bytes, total_bytes, validator, payload, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            ON CONFLICT(file_id) DO UPDATE SET
+                url = excluded.url,
+                save_path = excluded.save_path,
+                event_name = excluded.event_name,
+                priority = excluded.priority,
+                position = excluded.position,
+                state = excluded.state,
+                downloaded_bytes = excluded.downloaded_bytes,
+                total_bytes = excluded.total_bytes,
+                validator = excluded.validator,
+                payload = excluded.payload,
+                updated_at = excluded.updated_at
+            ",
+            params![
+                record.file_id,
+                record.url,
+                record.save_path,
+                record.event_name,
+                record.priority,
+                record.position,
+                record.state,
+                record.downloaded_bytes,
+                record.total_bytes,
+                record.validator,
+                record.payload,
+                Utc::now().timestamp_millis(),
+            ],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+// 更新下载进度和续传校验值
+pub async fn update_download_progress(
+    file_id: String,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    validator: Option<String>,
+) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "
+            UPDATE download_queue
+            SET downloaded_bytes = ?1, total_bytes = ?2, validator = ?3, updated_at = ?4
+            WHERE file_id = ?5
+            ",
+            params![
+                downloaded_bytes,
+                total_bytes,
+                validator,
+                Utc::now().timestamp_millis(),
+                file_id,
+            ],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// 按当前队列更新顺序、优先级和状态，`entries` 为 (file_id, priority, state)
+pub async fn save_download_queue_order(entries: Vec<(String, i32, String)>) -> Result<()> {
+    with_conn(move |conn| {
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "
+                UPDATE download_queue SET position = ?1, priority = ?2, state = ?3
+                WHERE file_id = ?4
+                ",
+            )?;
+            for (position, (file_id, priority, state)) in entries.into_iter().enumerate() {
+                stmt.execute(params![position as i64, priority, state, file_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    })
+    .await
+}
+
+pub async fn remove_download_record(file_id: String) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute("DELETE FROM download_queue WHERE file_id = ?1", params![file_id])?;
+        Ok(())
+    })
+    .await
+}
+
+pub async fn get_download_records() -> Result<Vec<DownloadQueueRecord>> {
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare(
+            "
+            SELECT file_id, url, save_path, event_name, priority, position, state,
+                   downloaded_bytes, total_bytes, validator, payload
+            FROM download_queue
+            ORDER BY position ASC, updated_at ASC
+            ",
+        )?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(DownloadQueueRecord {
+                    file_id: row.get(0)?,
+                    url: row.get(1)?,
+                    save_path: row.get(2)?,
+                    event_name: row.get(3)?,
+                    priority: row.get(4)?,
+                    position: row.get(5)?,
+                    state: row.get(6)?,
+                    downloaded_bytes: row.get(7)?,
+                    total_bytes: row.get(8)?,
+                    validator: row.get(9)?,
+                    payload: row.get(10)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(records)
+    })
+    .await
+}
+
+/// 缩略图缓存记录，path 为空表示尚未下载
+#[derive(Debug, Clone)]
+pub struct ImageCacheEntry {
+    pub source: String,
+    pub trainer_id: String,
+    pub url: String,
+    pub path: Option<String>,
+    pub size: u64,
+    pub content_type: Option<String>,
+    pub last_access: i64,
+}
+
+/// 缩略图所属的修改器（来源 + 修改器 ID）
+pub type ImageKey = (String, String);
+
+// 登记缩略图地址，地址变化时清空已下载的文件记录，返回需要下载的条目
+pub async fn register_image_urls(images: Vec<(ImageKey, String)>) -> Result<Vec<(ImageKey, String)>> {
+    with_conn(move |conn| {
+        let tx = conn.transaction()?;
+        let mut pending = Vec::new();
+
+        {
+            let mut stmt = tx.prepare(
+                "
+                INSERT INTO image_cache (source, trainer_id, url) VALUES (?1, ?2, ?3)
+                ON CONFLICT(source, trainer_id) DO UPDATE SET
+                    path = CASE WHEN image_cache.url = excluded.url THEN image_cache.path ELSE NULL END,
+                    url = excluded.url
+                RETURNING path
+                ",
+            )?;
+
+            for ((source, trainer_id), url) in images {
+                let path: Option<String> =
+                    stmt.query_row(params![source, trainer_id, url], |row| row.get(0))?;
+                if path.is_none() {
+                    pending.push(((source, trainer_id), url));
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(pending)
+    })
+    .await
+}
+
+// 删除全部缩略图文件和记录
+fn remove_image_files(conn: &Connection) -> Result<()> {
+    let paths = {
+        let mut stmt = conn.prepare("SELECT path FROM image_cache WHERE path IS NOT NULL")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+    conn.execute("DELETE FROM image_cache", [])?;
+    Ok(())
+}
+
+pub async fn get_image_entry(source: &str, trainer_id: &str) -> Result<Option<ImageCacheEntry>> {
+    let source = source.to_string();
+    let trainer_id = trainer_id.to_string();
+    with_conn(move |conn| {
+        Ok(conn
+            .query_row(
+                "
+                SELECT source, trainer_id, url, path, size, content_type, last_access
+                FROM image_cache WHERE source = ?1 AND trainer_id = ?2
+                ",
+                params![source, trainer_id],
+                |row| {
+                    Ok(ImageCacheEntry {
+                        source: row.get(0)?,
+                        trainer_id: row.get(1)?,
+                        url: row.get(2)?,
+                        path: row.get(3)?,
+                        size: row.get(4)?,
+                        content_type: row.get(5)?,
+                        last_access: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?)
+    })
+    .await
+}
+
+pub async fn save_image_entry(entry: ImageCacheEntry) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "
+            INSERT INTO image_cache (source, trainer_id, url, path, size, content_type, last_access)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(source, trainer_id) DO UPDATE SET
+                url = excluded.url,
+                path = excluded.path,
+                size = excluded.size,
+                content_type = excluded.content_type,
+                last_access = excluded.last_access
+            ",
+            params![
+                entry.source,
+                entry.trainer_id,
+                entry.url,
+                entry.path,
+                entry.size,
+                entry.content_type,
+                entry.last_access,
+            ],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+pub async fn touch_image_entry(source: &str, trainer_id: &str, last_access: i64) -> Result<()> {
+    let source = source.to_string();
+    let trainer_id = trainer_id.to_string();
+    with_conn(move |conn| {
+        conn.execute(
+            "UPDATE image_cache SET last_access = ?1 WHERE source = ?2 AND trainer_id = ?3",
+            params![last_access, source, trainer_id],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// 按 LRU 淘汰已下载的缩略图，直到总大小不超过 `max_bytes`，返回被淘汰的文件路径
+pub async fn evict_images(max_bytes: u64) -> Result<Vec<String>> {
+    with_conn(move |conn| {
+        let tx = conn.transaction()?;
+        let mut total: u64 = tx.query_row(
+            "SELECT COALESCE(SUM(size), 0) FROM image_cache WHERE path IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let mut evicted = Vec::new();
+        if total > max_bytes {
+            let candidates = {
+                let mut stmt = tx.prepare(
+                    "
+                    SELECT rowid, path, size FROM image_cache
+                    WHERE path IS NOT NULL
+                    ORDER BY last_access ASC
+                    ",
+                )?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, u64>(2)?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                rows
+            };
+
+            for (rowid, path, size) in candidates {
+                if total <= max_bytes {
+                    break;
+                }
+                tx.execute(
+                    "UPDATE image_cache SET path = NULL, size = 0 WHERE rowid = ?1",
+                    params![rowid],
+                )?;
+                total = total.saturating_sub(size);
+                evicted.push(path);
+            }
+        }
+
+        tx.commit()?;
+        Ok(evicted)
+    })
+    .await
+}
+
+// 写入别名，已存在时更新展示文本和语言
+pub async fn upsert_aliases(aliases: Vec<GameAlias>) -> Result<usize> {
+    with_conn(move |conn| {
+        let tx = conn.transaction()?;
+        let count = aliases.len();
+
+        {
+            let mut stmt = tx.prepare(
+                "
+                INSERT INTO game_aliases (alias_key, alias, source, trainer_id, language)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT(alias_key, source, trainer_id) DO UPDATE SET
+                    alias = excluded.alias,
+                    language = excluded.language
+                ",
+            )?;
+
+            for a in aliases {
+                stmt.execute(params![a.key(), a.alias, a.source, a.trainer_id, a.language])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(count)
+    })
+    .await
+}
+
+pub async fn get_aliases(trainer_id: Option<String>) -> Result<Vec<GameAlias>> {
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare(
+            "
+            SELECT alias, trainer_id, source, language FROM game_aliases
+            WHERE ?1 IS NULL OR trainer_id = ?1
+            ORDER BY trainer_id, alias
+            ",
+        )?;
+
+        let rows = stmt.query_map(params![trainer_id], |row| {
+            Ok(GameAlias {
+                alias: row.get(0)?,
+                trainer_id: row.get(1)?,
+                source: row.get(2)?,
+                language: row.get(3)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for alias in rows {
+            result.push(alias?);
+        }
+        Ok(result)
+    })
+    .await
+}
+
+pub async fn remove_alias(alias: GameAlias) -> Result<()> {
+    with_conn(move |conn| {
+        conn.execute(
+            "DELETE FROM game_aliases WHERE alias_key = ?1 AND source = ?2 AND trainer_id = ?3",
+            params![alias.key(), alias.source, alias.trainer_id],
+        )?;
         Ok(())
     })
     .await
 }
 
+/// 按别名键查找目标条目（来源、ID、目录中的名称）
+///
+/// 只返回通用别名和与 `language` 匹配的别名，"zh" 可匹配 "zh-CN"。
+pub async fn find_alias_targets(
+    key: String,
+    language: String,
+) -> Result<Vec<(String, String, Option<String>)>> {
+    with_conn(move |conn| {
+        let mut stmt = conn.prepare(
+            "
+            SELECT a.source, a.trainer_id, c.name
+            FROM game_aliases a
+            LEFT JOIN catalog c ON c.source = a.source AND c.id = a.trainer_id
+            WHERE a.alias_key = ?1
+              AND (a.language = ''
+                   OR lower(a.language) = lower(?2)
+                   OR lower(?2) LIKE lower(a.language) || '-%')
+            ORDER BY a.trainer_id
+            ",
+        )?;
+
+        let rows = stmt.query_map(params![key, language], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        let mut result = Vec::new();
+        for target in rows {
+            result.push(target?);
+        }
+        Ok(result)
+    })
+    .await
+}
+
 pub async fn get_all_keys() -> Result<Vec<String>> {
     with_conn(move |conn| {
         let mut keys = Vec::new();
@@ -548,6 +1849,12 @@ pub async fn clear_all() -> Result<()> {
         conn.execute("DELETE FROM downloaded_trainers", [])?;
         conn.execute("DELETE FROM trainer_cache", [])?;
         conn.execute("DELETE FROM search_cache", [])?;
+        remove_http_cache_before(conn, i64::MAX)?;
+        conn.execute("DELETE FROM catalog", [])?;
+        conn.execute("DELETE FROM catalog_fts", [])?;
+        conn.execute("DELETE FROM catalog_sync", [])?;
+        conn.execute("DELETE FROM download_queue", [])?;
+        remove_image_files(conn)?;
         Ok(())
     })
     .await
@@ -645,3 +1952,183 @@ pub async fn migrate_from_local_storage(local_data: HashMap<String, Value>) -> R
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scraper;
+
+    #[test]
+    fn viewing_detail_updates_listed_catalog_row() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let listed =
+            scraper::parse_trainer_list(include_str!("../../fixtures/fling/list_page.html")).unwrap();
+        let count = listed.len() as u32;
+        upsert_catalog_rows(&conn, listed, 1).unwrap();
+
+        let detail =
+            scraper::parse_trainer_detail(include_str!("../../fixtures/fling/detail_page.html")).unwrap();
+        upsert_catalog_rows(&conn, vec![detail], 2).unwrap();
+
+        assert_eq!(count_catalog_rows(&conn, None).unwrap(), count);
+        let fts_rows: u32 = conn
+            .query_row("SELECT COUNT(*) FROM catalog_fts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fts_rows, count);
+
+        // 详情页的功能列表写入了列表页的同一条目
+        let options: String = conn
+            .query_row(
+                "SELECT options FROM catalog WHERE id = 'elden-ring-trainer'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_ne!(options, "[]");
+    }
+
+    #[test]
+    fn legacy_install_records_are_keyed_by_source() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE installed_trainers (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                version TEXT NOT NULL,
+                game_version TEXT NOT NULL,
+                download_url TEXT NOT NULL,
+                description TEXT NOT NULL,
+                thumbnail TEXT NOT NULL,
+                download_count INTEGER NOT NULL,
+                last_update TEXT NOT NULL,
+                installed_path TEXT NOT NULL,
+                install_time TEXT NOT NULL,
+                last_launch_time TEXT
+            );
+            INSERT INTO installed_trainers VALUES (
+                'elden-ring', 'Elden Ring', 'v1.0', '1.02', '', '', '', 0,
+                '2024-01-01', 'C:/trainers/elden-ring', '2024-01-02', NULL
+            );
+            ",
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+
+        let (source, path): (String, String) = conn
+            .query_row(
+                "SELECT source, installed_path FROM installed_trainers",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(source, "fling");
+        assert_eq!(path, "C:/trainers/elden-ring");
+
+        // 其他来源的同名修改器不会覆盖已有的安装记录
+        conn.execute(
+            "
+            INSERT INTO installed_trainers (
+                id, name, version, game_version, download_url, description, thumbnail,
+                download_count, last_update, source, installed_path, install_time
+            ) VALUES ('elden-ring', 'Elden Ring', 'v2', '', '', '', '', 0, '', 'team', 'D:/x', '')
+            ",
+            [],
+        )
+        .unwrap();
+        let rows: u32 = conn
+            .query_row("SELECT COUNT(*) FROM installed_trainers", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn legacy_detail_ids_are_migrated_with_install_dirs() {
+        let root = std::env::temp_dir().join(format!("gmm-id-migration-{}", std::process::id()));
+        let old_dir = root.join("Elden Ring_elden-ring");
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::write(
+            old_dir.join("trainer.json"),
+            r#"{"trainer":{"id":"elden-ring"},"install_path":"old"}"#,
+        )
+        .unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn.execute("PRAGMA user_version = 0", []).unwrap();
+        conn.execute(
+            "
+            INSERT INTO installed_trainers (
+                id, name, version, game_version, download_url, description, thumbnail,
+                download_count, last_update, installed_path, install_time
+            ) VALUES ('elden-ring', 'Elden Ring', 'v1', '', '', '', '', 0, '', ?1, '')
+            ",
+            params![old_dir.to_string_lossy()],
+        )
+        .unwrap();
+        conn.execute(
+            "
+            INSERT INTO trainer_revisions (
+                source, trainer_id, version, game_version, last_update, recorded_at
+            ) VALUES ('fling', 'elden-ring', 'v1', '', '', '')
+            ",
+            [],
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+
+        // 安装记录改为列表页的 ID，目录随之重命名
+        let new_dir = root.join("Elden Ring_elden-ring-trainer");
+        let (id, path): (String, String) = conn
+            .query_row("SELECT id, installed_path FROM installed_trainers", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(id, "elden-ring-trainer");
+        assert_eq!(PathBuf::from(path), new_dir);
+        assert!(!old_dir.exists());
+        let info: Value =
+            serde_json::from_str(&std::fs::read_to_string(new_dir.join("trainer.json")).unwrap()).unwrap();
+        assert_eq!(info["trainer"]["id"], "elden-ring-trainer");
+
+        // 修订历史归到同一 ID 下
+        let revision_id: String = conn
+            .query_row("SELECT trainer_id FROM trainer_revisions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(revision_id, "elden-ring-trainer");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn catalog_keeps_checksum_and_mirrors_when_listing_has_no_download() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let mut detail =
+            scraper::parse_trainer_detail(include_str!("../../fixtures/fling/detail_page.html")).unwrap();
+        detail.sha256 = Some("ab".repeat(32));
+        detail.mirrors = vec!["https://mirror.example.com/elden-ring.zip".to_string()];
+        let mut listed = detail.clone();
+        listed.download_url = String::new();
+        listed.sha256 = None;
+        listed.mirrors = Vec::new();
+        upsert_catalog_rows(&conn, vec![detail.clone()], 1).unwrap();
+        upsert_catalog_rows(&conn, vec![listed], 2).unwrap();
+
+        let stored = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM catalog c WHERE c.source = ?1 AND c.id = ?2",
+                    CATALOG_COLUMNS
+                ),
+                params![detail.source, detail.id],
+                catalog_row_to_trainer,
+            )
+            .unwrap();
+        assert_eq!(stored.sha256, detail.sha256);
+        assert_eq!(stored.mirrors, detail.mirrors);
+    }
+}
@@ -1,13 +1,18 @@
 use crate::api::error::{AppError, AppResult};
-use crate::api::trainer::PaginatedResponse;
+use crate::api::trainer::{PaginatedResponse, TrainerDetail};
 use crate::models::trainer::{Trainer, TrainerInstallInfo};
-use crate::services::download_manager;
+use crate::services::alias;
+use crate::services::download_manager::{self, DownloadTask, EnqueuedDownload};
+use crate::services::image_cache;
+use crate::services::offline;
+use crate::services::revision;
 use crate::services::storage;
-use crate::services::scraper;
 use crate::services::settings;
+use crate::services::sources;
 use crate::utils::path::sanitize_filename;
 use crate::utils::zip::extract_zip;
 use chrono::{Local, Utc};
+use log::info;
 use std::fs;
 use std::io::Read;
 use std::io::Write;
@@ -15,46 +20,89 @@ use std::io::Write;
 use std::os::windows::ffi::OsStrExt;
 use std::path::PathBuf;
 use tauri::Emitter;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::Shell::ShellExecuteW;
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOW;
 
-pub async fn fetch_trainers(page: u32) -> AppResult<PaginatedResponse<Trainer>> {
-    let url = format!("https://flingtrainer.com/page/{}/", page);
-    let response = reqwest::get(&url).await?;
-    let html = response.text().await?;
-    let trainers = scraper::parse_trainer_list(&html)?;
-
-    // 这里需要从网页中解析总数，暂时使用固定值
-    let total = 120; // 假设总共有120个训练器
-
-    Ok(PaginatedResponse { trainers, total })
+pub async fn fetch_trainers(
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let online = async {
+        let response = sources::get_source(source.as_deref())?.list(page).await?;
+        image_cache::prefetch(&response.trainers);
+        Ok(response)
+    };
+    offline::run(online, || offline::list(page, source.clone())).await
 }
 
-pub async fn search_trainers(query: String, page: u32) -> AppResult<PaginatedResponse<Trainer>> {
-    let url = format!("https://flingtrainer.com/page/{}/?s={}", page, query);
-    let response = reqwest::get(&url).await?;
-    let html = response.text().await?;
-    let trainers = scraper::parse_trainer_list(&html)?;
-
-    // 这里需要从搜索结果页面解析总数，暂时使用固定值
-    let total = trainers.len() as u32;
-
-    Ok(PaginatedResponse { trainers, total })
+pub async fn search_trainers(
+    query: String,
+    page: u32,
+    source: Option<String>,
+) -> AppResult<PaginatedResponse<Trainer>> {
+    let online = async {
+        // 站点只收录英文名称，命中别名时改用对应的英文名称搜索
+        let query = alias::translate_query(&query).await;
+        let response = match &source {
+            Some(id) => sources::get_source(Some(id))?.search(&query, page).await?,
+            // 未指定来源时在所有来源中搜索
+            None => sources::search_all(&query, page).await?,
+        };
+        image_cache::prefetch(&response.trainers);
+        Ok(response)
+    };
+    offline::run(online, || offline::search(query.clone(), page, source.clone())).await
 }
 
-pub async fn get_trainer_detail(id: String) -> AppResult<Trainer> {
-    let url = format!("https://flingtrainer.com/trainer/{}/", id);
-    let response = reqwest::get(&url).await?;
-    let html = response.text().await?;
-    scraper::parse_trainer_detail(&html)
+pub async fn get_trainer_detail(id: String, source: Option<String>) -> AppResult<TrainerDetail> {
+    let online = async {
+        let trainer = sources::get_source(source.as_deref())?.detail(&id).await?;
+
+        // 详情页包含功能列表，写回本地目录供全文搜索使用
+        if let Err(e) = storage::upsert_catalog_trainers(vec![trainer.clone()]).await {
+            println!("更新本地目录失败: {}", e);
+        }
+        revision::record(&trainer).await;
+        Ok(TrainerDetail {
+            trainer,
+            stale: false,
+        })
+    };
+    offline::run(online, || async {
+        Ok(TrainerDetail {
+            trainer: offline::detail(id.clone(), source.clone()).await?,
+            stale: true,
+        })
+    })
+    .await
 }
 
 pub async fn download_trainer<R: tauri::Runtime>(
     app_handle: tauri::AppHandle<R>,
-    trainer: Trainer,
+    mut trainer: Trainer,
+    variant: Option<usize>,
+    priority: i32,
 ) -> AppResult<PathBuf> {
+    // 指定了下载项时使用对应附件
+    if let Some(index) = variant {
+        let download = trainer.downloads.get(index).ok_or_else(|| {
+            AppError::ValidationError(format!("下载项不存在: {}", index))
+        })?;
+        trainer.download_url = download.url.clone();
+        trainer.mirrors = download.mirrors.clone();
+        trainer.sha256 = download.sha256.clone();
+    }
+
+    // 列表页数据可能没有下载地址，交给来源解析
+    if trainer.download_url.is_empty() {
+        trainer.download_url = sources::get_source(Some(&trainer.source))?
+            .resolve_download(&trainer)
+            .await?;
+    }
+
     println!(
         "开始下载修改器: {} ({})",
         trainer.name, trainer.download_url
@@ -63,11 +111,6 @@ pub async fn download_trainer<R: tauri::Runtime>(
     let download_dir = settings::get_download_path()?;
     fs::create_dir_all(&download_dir)?;
 
-    // 生成标准化的修改器目录名
-    let safe_name = sanitize_filename(&trainer.name);
-    let trainer_dir_name = format!("{}_{}", safe_name, trainer.id);
-    let final_dir = download_dir.join(&trainer_dir_name);
-
     // 使用临时目录，确保失败不污染正式目录
     let staging_dir = download_dir.join(format!(
         "._tmp_{}_{}",
@@ -85,15 +128,119 @@ pub async fn download_trainer<R: tauri::Runtime>(
         fs::remove_file(&temp_zip)?;
     }
 
-    // 使用下载管理器下载文件
-    download_manager::download_file_with_progress(
-        app_handle.clone(),
-        &trainer.download_url,
-        &trainer.id,
-        &temp_zip,
-        "download-progress",
-    )
-    .await?;
+    // 加入下载队列，修改器信息随队列保存以便重启后恢复
+    let mut task = DownloadTask::new(&trainer.id, &trainer.download_url, temp_zip, "download-progress");
+    task.priority = priority;
+    task.payload = serde_json::to_string(&trainer)?;
+    task.expected_sha256 = trainer.sha256.clone();
+    task.mirrors = trainer.mirrors.clone();
+    let download = match download_manager::enqueue(task) {
+        Ok(download) => download,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+    };
+
+    install_download(app_handle, trainer, download).await
+}
+
+/// 恢复上次退出时未完成的下载
+///
+/// 开启自动继续时按原顺序重新排队，否则恢复为暂停状态并询问是否继续。
+pub async fn restore_downloads<R: tauri::Runtime>(app_handle: tauri::AppHandle<R>) -> AppResult<usize> {
+    let auto_resume = settings::load_settings()
+        .map(|s| s.auto_resume_downloads)
+        .unwrap_or(false);
+
+    let mut restored = 0;
+    for mut task in download_manager::saved_downloads().await? {
+        let trainer: Trainer = match serde_json::from_str(&task.payload) {
+            Ok(trainer) => trainer,
+            Err(e) => {
+                println!("下载记录无效，已忽略 {}: {}", task.file_id, e);
+                download_manager::forget_download(&task.file_id).await;
+                continue;
+            }
+        };
+
+        // 临时目录被删除时从头下载
+        if let Some(staging_dir) = task.save_path.parent() {
+            fs::create_dir_all(staging_dir)?;
+        }
+        task.paused |= !auto_resume;
+        task.expected_sha256 = trainer.sha256.clone();
+        task.mirrors = trainer.mirrors.clone();
+
+        let download = match download_manager::enqueue(task) {
+            Ok(download) => download,
+            Err(e) => {
+                println!("恢复下载失败: {}", e);
+                continue;
+            }
+        };
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let trainer_id = trainer.id.clone();
+            if let Err(e) = install_download(app_handle, trainer, download).await {
+                println!("恢复的下载未完成 {}: {}", trainer_id, e);
+            }
+        });
+        restored += 1;
+    }
+
+    if restored > 0 && !auto_resume {
+        app_handle
+            .dialog()
+            .message(format!("有 {} 个未完成的下载，是否继续？", restored))
+            .title("恢复下载")
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                "继续下载".to_string(),
+                "稍后".to_string(),
+            ))
+            .show(|confirmed| {
+                if confirmed {
+                    download_manager::resume_all_downloads();
+                }
+            });
+    }
+    Ok(restored)
+}
+
+// 等待下载完成后解压并安装到正式目录
+async fn install_download<R: tauri::Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    trainer: Trainer,
+    download: EnqueuedDownload,
+) -> AppResult<PathBuf> {
+    let temp_zip = download.save_path().clone();
+    let staging_dir = temp_zip
+        .parent()
+        .map(PathBuf::from)
+        .ok_or_else(|| AppError::ExecutionError("临时文件路径无效".to_string()))?;
+
+    let download_dir = settings::get_download_path()?;
+    fs::create_dir_all(&download_dir)?;
+
+    // 生成标准化的修改器目录名
+    let safe_name = sanitize_filename(&trainer.name);
+    // 其他来源的目录名带上来源，避免与 FLiNG 的同名修改器共用目录
+    let trainer_dir_name = if trainer.source == sources::DEFAULT_SOURCE {
+        format!("{}_{}", safe_name, trainer.id)
+    } else {
+        format!("{}_{}_{}", safe_name, trainer.source, trainer.id)
+    };
+    let final_dir = download_dir.join(&trainer_dir_name);
+
+    // 使用下载管理器下载文件，失败、取消或校验不通过时清理临时目录
+    let outcome = match download_manager::download_file_with_progress(app_handle.clone(), download).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+    };
+    info!("安装包 SHA-256: {} (下载地址: {})", outcome.sha256, outcome.url);
 
     // 验证临时文件
     if !temp_zip.exists() || fs::metadata(&temp_zip)?.len() == 0 {
@@ -185,13 +332,20 @@ pub async fn download_trainer<R: tauri::Runtime>(
         install_path: final_dir.to_string_lossy().to_string(),
         install_time: install_time.clone(),
         last_launch_time: None,
+        installed_sha256: Some(outcome.sha256.clone()),
     };
 
     let info_json = serde_json::to_string_pretty(&install_info)?;
     let mut info_file = fs::File::create(final_dir.join("trainer.json"))?;
     info_file.write_all(info_json.as_bytes())?;
 
-    // 同步数据库，确保前端状态与文件一致
+    // 同步数据库，确保前端状态与文件一致；旧的安装记录会被覆盖，先存为历史版本
+    match storage::get_installed_trainer_by_id(&trainer.source, &trainer.id).await {
+        Ok(Some(previous)) => revision::record_installed(&previous).await,
+        Ok(None) => {}
+        Err(e) => println!("读取旧安装记录失败 {}: {}", trainer.id, e),
+    }
+    revision::record(&trainer).await;
     let installed_record = crate::models::trainer::InstalledTrainer {
         id: trainer.id.clone(),
         name: trainer.name.clone(),
@@ -202,9 +356,12 @@ pub async fn download_trainer<R: tauri::Runtime>(
         thumbnail: trainer.thumbnail.clone(),
         download_count: trainer.download_count,
         last_update: trainer.last_update.clone(),
+        source: trainer.source.clone(),
+        options: trainer.options.clone(),
         installed_path: final_dir.to_string_lossy().to_string(),
         install_time: install_time.clone(),
         last_launch_time: install_time.clone(),
+        installed_sha256: Some(outcome.sha256),
     };
     storage::upsert_installed_trainer(installed_record)
         .await
@@ -223,7 +380,8 @@ pub async fn download_trainer<R: tauri::Runtime>(
             "progress": 100.0,
             "downloaded_bytes": fs::metadata(&temp_zip).map(|m| m.len()).unwrap_or(0),
             "total_bytes": fs::metadata(&temp_zip).map(|m| m.len()).unwrap_or(0),
-            "speed": null
+            "speed": null,
+            "url": outcome.url
         }),
     );
 
@@ -271,9 +429,10 @@ fn is_exe_file(file_path: &PathBuf) -> bool {
     false
 }
 
-pub async fn launch_trainer(trainer_id: String) -> AppResult<()> {
+pub async fn launch_trainer(trainer_id: String, source: Option<String>) -> AppResult<()> {
+    let source = source.unwrap_or_else(|| sources::DEFAULT_SOURCE.to_string());
     // 优先从数据库读取安装路径，确保数据与文件同步
-    let trainer_record = storage::get_installed_trainer_by_id(&trainer_id)
+    let trainer_record = storage::get_installed_trainer_by_id(&source, &trainer_id)
         .await
         .map_err(|e| AppError::ExecutionError(format!("查询安装记录失败: {}", e)))?;
 
@@ -293,7 +452,9 @@ pub async fn launch_trainer(trainer_id: String) -> AppResult<()> {
                                 if let Ok(install_info) =
                                     serde_json::from_str::<TrainerInstallInfo>(&content)
                                 {
-                                    if install_info.trainer.id == trainer_id {
+                                    if install_info.trainer.id == trainer_id
+                                        && install_info.trainer.source == source
+                                    {
                                         fallback_path = Some(path);
                                         break;
                                     }
@@ -392,16 +553,17 @@ pub async fn launch_trainer(trainer_id: String) -> AppResult<()> {
     }
 
     // 同步数据库启动时间
-    storage::update_last_launch_time(&trainer_id, &now)
+    storage::update_last_launch_time(&source, &trainer_id, &now)
         .await
         .map_err(|e| AppError::ExecutionError(format!("更新启动时间失败: {}", e)))?;
 
     Ok(())
 }
 
-pub async fn delete_trainer(trainer_id: String) -> AppResult<()> {
+pub async fn delete_trainer(trainer_id: String, source: Option<String>) -> AppResult<()> {
+    let source = source.unwrap_or_else(|| sources::DEFAULT_SOURCE.to_string());
     // 获取路径信息
-    let installed = storage::get_installed_trainer_by_id(&trainer_id)
+    let installed = storage::get_installed_trainer_by_id(&source, &trainer_id)
         .await
         .map_err(|e| AppError::ExecutionError(format!("查询安装记录失败: {}", e)))?;
 
@@ -424,7 +586,9 @@ pub async fn delete_trainer(trainer_id: String) -> AppResult<()> {
                                 if let Ok(install_info) =
                                     serde_json::from_str::<TrainerInstallInfo>(&content)
                                 {
-                                    if install_info.trainer.id == trainer_id {
+                                    if install_info.trainer.id == trainer_id
+                                        && install_info.trainer.source == source
+                                    {
                                         fs::remove_dir_all(path)?;
                                         break;
                                     }
@@ -438,10 +602,10 @@ pub async fn delete_trainer(trainer_id: String) -> AppResult<()> {
     }
 
     // 同步数据库
-    storage::remove_installed_trainer(&trainer_id)
+    storage::remove_installed_trainer(&source, &trainer_id)
         .await
         .map_err(|e| AppError::ExecutionError(format!("移除安装记录失败: {}", e)))?;
-    storage::remove_downloaded_trainer(&trainer_id)
+    storage::remove_downloaded_trainer(&source, &trainer_id)
         .await
         .map_err(|e| AppError::ExecutionError(format!("移除下载记录失败: {}", e)))?;
 
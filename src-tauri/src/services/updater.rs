@@ -1,6 +1,7 @@
 use crate::api::error::{AppError, AppResult};
+use crate::services::http;
+use crate::services::settings;
 use log::info;
-use reqwest;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
@@ -45,18 +46,11 @@ fn send_progress_update(window: &Window, status: &str, progress: u32, message: &
 
 // 检查更新
 pub async fn check_update(current_version: String) -> AppResult<UpdateInfo> {
-    let client = reqwest::Client::new();
-
     // 获取最新版本信息（请替换为实际的GitHub仓库地址）
     let releases_url = "https://api.github.com/repos/xinggaoya/GameModMaster/releases/latest";
     
     // 发送请求获取最新版本信息
-    let response = client
-        .get(releases_url)
-        .header("User-Agent", "GameModMaster")
-        .send()
-        .await
-        .map_err(|e| AppError::RequestError(e))?;
+    let response = http::get(releases_url).await?;
 
     if !response.status().is_success() {
         return Err(AppError::DownloadError(format!(
@@ -138,35 +132,49 @@ pub async fn download_and_install_update(
     // 发送开始下载通知
     send_progress_update(&window, "downloading", 0, "正在准备下载...")?;
 
-    // 使用代理下载
-    let client = reqwest::Client::new();
-    
-    // 原始URL和代理URL
+    // 原始URL和加速镜像URL
     let original_url = download_url.clone();
-    let proxy_url = format!("https://gh-proxy.com/{}", original_url);
-    
-    info!("尝试通过代理下载: {}", proxy_url);
-    send_progress_update(&window, "downloading", 5, "尝试通过代理下载...")?;
-    
-    // 先尝试使用代理下载
-    let response_result = client.get(&proxy_url).send().await;
-    
-    // 如果代理下载失败，使用原始URL
-    let response = if let Ok(resp) = response_result {
-        if resp.status().is_success() {
-            info!("使用代理下载成功");
-            resp
-        } else {
-            info!("代理下载失败，尝试直接下载: {}", original_url);
-            send_progress_update(&window, "downloading", 5, "代理下载失败，尝试直接下载...")?;
-            client.get(&original_url).send().await?
-        }
+    let mirror_url = format!("https://gh-proxy.com/{}", original_url);
+
+    // 配置了代理时优先直连 GitHub，否则优先使用加速镜像
+    let proxy_configured = settings::load_settings()
+        .map(|s| s.proxy.is_manual())
+        .unwrap_or(false);
+    let candidates = if proxy_configured {
+        [(original_url.as_str(), "直接下载"), (mirror_url.as_str(), "镜像下载")]
     } else {
-        info!("代理下载失败，尝试直接下载: {}", original_url);
-        send_progress_update(&window, "downloading", 5, "代理下载失败，尝试直接下载...")?;
-        client.get(&original_url).send().await?
+        [(mirror_url.as_str(), "镜像下载"), (original_url.as_str(), "直接下载")]
     };
 
+    let mut response = None;
+    let mut last_error = None;
+    for (url, label) in candidates {
+        info!("尝试{}: {}", label, url);
+        send_progress_update(&window, "downloading", 5, &format!("尝试{}...", label))?;
+
+        match http::get(url).await {
+            Ok(resp) if resp.status().is_success() => {
+                info!("{}成功", label);
+                response = Some(resp);
+                break;
+            }
+            Ok(resp) => {
+                info!("{}失败，HTTP状态码: {}", label, resp.status());
+                last_error = Some(AppError::DownloadError(format!(
+                    "下载失败，HTTP状态码: {}",
+                    resp.status()
+                )));
+            }
+            Err(e) => {
+                info!("{}失败: {}", label, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    let response = response.ok_or_else(|| {
+        last_error.unwrap_or_else(|| AppError::DownloadError("没有可用的下载地址".to_string()))
+    })?;
+
     // 获取文件大小
     let total_size = response
         .content_length()
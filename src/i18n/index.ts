@@ -181,8 +181,13 @@ const messages = {
         extracting: '解压中',
         completed: '已完成',
         error: '错误',
+        cancelled: '已取消',
+        paused: '已暂停',
+        queued: '排队中',
       },
       cancel: '取消',
+      pause: '暂停',
+      resume: '继续',
     },
     gameCard: {
       viewDetail: '查看详情',
@@ -403,8 +408,13 @@ const messages = {
         extracting: 'Extracting',
         completed: 'Completed',
         error: 'Error',
+        cancelled: 'Cancelled',
+        paused: 'Paused',
+        queued: 'Queued',
       },
       cancel: 'Cancel',
+      pause: 'Pause',
+      resume: 'Resume',
     },
     gameCard: {
       viewDetail: 'View details',
@@ -625,8 +635,13 @@ const messages = {
         extracting: 'Extrayendo',
         completed: 'Completado',
         error: 'Error',
+        cancelled: 'Cancelado',
+        paused: 'En pausa',
+        queued: 'En cola',
       },
       cancel: 'Cancelar',
+      pause: 'Pausar',
+      resume: 'Reanudar',
     },
     gameCard: {
       viewDetail: 'Ver detalles',
@@ -847,8 +862,13 @@ const messages = {
         extracting: 'Extraction',
         completed: 'Terminé',
         error: 'Erreur',
+        cancelled: 'Annulé',
+        paused: 'En pause',
+        queued: 'En attente',
       },
       cancel: 'Annuler',
+      pause: 'Pause',
+      resume: 'Reprendre',
     },
     gameCard: {
       viewDetail: 'Voir les détails',
@@ -1069,8 +1089,13 @@ const messages = {
         extracting: '展開中',
         completed: '完了',
         error: 'エラー',
+        cancelled: 'キャンセル済み',
+        paused: '一時停止中',
+        queued: '待機中',
       },
       cancel: 'キャンセル',
+      pause: '一時停止',
+      resume: '再開',
     },
     gameCard: {
       viewDetail: '詳細を見る',
@@ -109,7 +109,9 @@ export const useTrainerStore = defineStore('trainer', () => {
   async function addTrainer(trainer: Trainer) {
     try {
       // 检查是否已经存在
-      const exists = installedTrainers.value.some((t) => t.id === trainer.id)
+      const exists = installedTrainers.value.some(
+        (t) => t.id === trainer.id && t.source === trainer.source,
+      )
       if (exists) {
         throw new Error('修改器已经安装')
       }
@@ -154,7 +156,9 @@ export const useTrainerStore = defineStore('trainer', () => {
   // 更新修改器信息
   async function updateTrainer(trainer: Trainer) {
     try {
-      const index = installedTrainers.value.findIndex((t) => t.id === trainer.id)
+      const index = installedTrainers.value.findIndex(
+        (t) => t.id === trainer.id && t.source === trainer.source,
+      )
       if (index === -1) {
         throw new Error('修改器不存在')
       }
@@ -231,12 +235,14 @@ export const useTrainerStore = defineStore('trainer', () => {
         invoke<{
           trainers: Trainer[]
           total: number
+          total_pages: number
+          current_page: number
+          has_next: boolean
         }>('fetch_trainers', { page }),
       )
 
       trainers.value = response.trainers
-      // 估算总页数（假设每页20条记录）
-      totalPages.value = Math.ceil(response.total / 20)
+      totalPages.value = response.total_pages
 
       // 缓存结果
       await StorageService.cacheTrainerList(page, response.trainers)
@@ -277,11 +283,14 @@ export const useTrainerStore = defineStore('trainer', () => {
         invoke<{
           trainers: Trainer[]
           total: number
-        }>('search_trainers', { query, page }),
+          total_pages: number
+          current_page: number
+          has_next: boolean
+        }>('search_local', { query, page }),
       )
 
       trainers.value = response.trainers
-      totalPages.value = Math.ceil(response.total / 20)
+      totalPages.value = response.total_pages
 
       // 缓存结果
       await StorageService.cacheSearchResults(query, page, response.trainers)
@@ -304,9 +313,9 @@ export const useTrainerStore = defineStore('trainer', () => {
   }
 
   // 获取修改器详情
-  async function getTrainerDetail(id: string) {
+  async function getTrainerDetail(id: string, source?: string) {
     try {
-      const result = await invoke<Trainer>('get_trainer_detail', { id })
+      const result = await invoke<Trainer>('get_trainer_detail', { id, source })
       return result
     } catch (err) {
       handleError(err, window.$message)
@@ -315,12 +324,14 @@ export const useTrainerStore = defineStore('trainer', () => {
   }
 
   // 下载修改器
-  async function downloadTrainer(trainer: Trainer) {
+  async function downloadTrainer(trainer: Trainer, variant?: number, priority?: number) {
     try {
-      const result = await invoke<string>('download_trainer', { trainer })
+      const result = await invoke<string>('download_trainer', { trainer, variant, priority })
 
       // 添加到下载记录
-      const exists = downloadedTrainers.value.some((t) => t.id === trainer.id)
+      const exists = downloadedTrainers.value.some(
+        (t) => t.id === trainer.id && t.source === trainer.source,
+      )
       if (!exists) {
         downloadedTrainers.value.push(trainer)
         await StorageService.saveDownloadedTrainers(downloadedTrainers.value)
@@ -344,12 +355,14 @@ export const useTrainerStore = defineStore('trainer', () => {
   }
 
   // 删除修改器
-  async function deleteTrainer(trainerId: string) {
+  async function deleteTrainer(trainerId: string, source: string) {
     try {
-      await invoke('delete_trainer', { trainerId })
+      await invoke('delete_trainer', { trainerId, source })
 
       // 从下载记录中删除
-      downloadedTrainers.value = downloadedTrainers.value.filter((t) => t.id !== trainerId)
+      downloadedTrainers.value = downloadedTrainers.value.filter(
+        (t) => t.id !== trainerId || t.source !== source,
+      )
       await StorageService.saveDownloadedTrainers(downloadedTrainers.value)
 
       return true
@@ -360,9 +373,9 @@ export const useTrainerStore = defineStore('trainer', () => {
   }
 
   // 启动修改器
-  async function launchTrainer(trainerId: string) {
+  async function launchTrainer(trainerId: string, source: string) {
     try {
-      await invoke('launch_trainer', { trainerId })
+      await invoke('launch_trainer', { trainerId, source })
       return true
     } catch (err) {
       handleError(err, window.$message)
@@ -1,3 +1,19 @@
+export interface TrainerOption {
+  hotkey: string
+  label: string
+  group: string | null
+}
+
+export interface TrainerDownload {
+  label: string
+  url: string
+  size: string
+  date: string
+  download_count: number
+  mirrors?: string[]
+  sha256?: string | null
+}
+
 export interface Trainer {
   id: string
   name: string
@@ -7,11 +23,26 @@ export interface Trainer {
   description: string
   thumbnail: string
   download_count: number
+  // ISO 日期（YYYY-MM-DD），无法识别时为网站原文
   last_update: string
+  // 来源标识，例如 "fling"
+  source: string
+  // 功能列表（热键 + 说明）
+  options: TrainerOption[]
+  // 可选下载项（不同游戏版本或平台）
+  downloads: TrainerDownload[]
+  // 备用下载地址（例如团队镜像）
+  mirrors?: string[]
+  // 下载文件的 SHA-256，来源提供时下载后校验
+  sha256?: string | null
+  // 离线时详情来自缓存或本地目录
+  stale?: boolean
   // 本地安装相关信息
   installed_path?: string
   install_time?: string
   last_launch_time?: string | null
+  // 安装包实际的 SHA-256
+  installed_sha256?: string | null
 }
 
 export interface InstalledTrainer extends Trainer {
@@ -13,6 +13,7 @@ export enum ErrorCode {
   NotFound = 9000,
   Permission = 10000,
   Execution = 11000,
+  Cancelled = 12000,
   Unknown = 99999,
 }
 
@@ -43,6 +44,9 @@ export enum ErrorSeverity {
 export function getErrorSeverity(code: ErrorCode): ErrorSeverity {
   // 根据错误代码确定严重性
   switch (code) {
+    case ErrorCode.Cancelled:
+      return ErrorSeverity.Info
+
     case ErrorCode.Network:
     case ErrorCode.Download:
       return ErrorSeverity.Warning
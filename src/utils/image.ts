@@ -0,0 +1,13 @@
+import { convertFileSrc } from '@tauri-apps/api/core'
+import type { Trainer } from '@/types'
+
+const PLACEHOLDER = '/placeholder.png'
+
+// 缩略图通过本地缓存协议加载，附带原始地址以便后端登记尚未预取的图片
+export const thumbnailSrc = (trainer: Pick<Trainer, 'id' | 'source' | 'thumbnail'>): string => {
+  if (!trainer.thumbnail) {
+    return PLACEHOLDER
+  }
+  const path = `${trainer.source}/${trainer.id}`
+  return `${convertFileSrc(path, 'gmm-img')}?src=${encodeURIComponent(trainer.thumbnail)}`
+}